@@ -0,0 +1,170 @@
+use crate::agent::Agent;
+use crate::{DiceCall, LiarsDiceGame};
+use rand::seq::IndexedRandom;
+use rand_chacha::ChaCha12Rng;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct IsmctsConfig {
+    /// Maximum number of determinizations to search
+    pub iterations: usize,
+    /// Stop searching once this much time has passed, even if there are iterations left
+    pub time_budget: Option<Duration>,
+    /// UCB1 exploration constant
+    pub exploration: f64,
+}
+
+impl Default for IsmctsConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 1000,
+            time_budget: None,
+            exploration: 0.7,
+        }
+    }
+}
+
+/// Single-observer Information-Set Monte Carlo Tree Search.
+///
+/// Every iteration re-rolls the dice the searching player can't see, then walks one shared tree of calls
+/// using the sampled game as the simulator. Leaves are played out to the end of the game by `rollout`.
+#[derive(Debug, Clone)]
+pub struct IsmctsAgent<R: Agent> {
+    config: IsmctsConfig,
+    rng: ChaCha12Rng,
+    rollout: R,
+}
+
+#[derive(Debug)]
+struct Node {
+    call: Option<DiceCall>,
+    children: Vec<usize>,
+    visits: u32,
+    /// How many times this node could have been picked, which stands in for the parent visits in UCB1
+    availability: u32,
+    /// Games won by whoever made `call` when the node was visited
+    wins: f64,
+}
+
+impl Node {
+    fn new(call: Option<DiceCall>) -> Self {
+        Self {
+            call,
+            children: vec![],
+            visits: 0,
+            availability: 1,
+            wins: 0.0,
+        }
+    }
+
+    fn ucb(&self, exploration: f64) -> f64 {
+        let visits = self.visits as f64;
+        self.wins / visits + exploration * ((self.availability as f64).ln() / visits).sqrt()
+    }
+}
+
+impl<R: Agent> IsmctsAgent<R> {
+    pub fn new(config: IsmctsConfig, rng: ChaCha12Rng, rollout: R) -> Self {
+        Self {
+            config,
+            rng,
+            rollout,
+        }
+    }
+
+    fn iterate(&mut self, nodes: &mut Vec<Node>, mut sim: LiarsDiceGame) {
+        // The nodes walked through, with whoever made the call leading to them
+        let mut path = vec![];
+        let mut node = 0;
+        // Selection and expansion
+        while !sim.is_complete() {
+            let actor = sim.current_player().unwrap();
+            let legal = sim.legal_calls();
+            let available: Vec<usize> = nodes[node]
+                .children
+                .iter()
+                .copied()
+                .filter(|child| legal.contains(&nodes[*child].call.unwrap()))
+                .collect();
+            for child in &available {
+                nodes[*child].availability += 1;
+            }
+            let untried: Vec<DiceCall> = legal
+                .into_iter()
+                .filter(|call| !available.iter().any(|c| nodes[*c].call == Some(*call)))
+                .collect();
+            let next = match untried.choose(&mut self.rng) {
+                Some(call) => {
+                    let child = nodes.len();
+                    nodes.push(Node::new(Some(*call)));
+                    nodes[node].children.push(child);
+                    child
+                }
+                None => *available
+                    .iter()
+                    .max_by(|a, b| {
+                        let exploration = self.config.exploration;
+                        nodes[**a]
+                            .ucb(exploration)
+                            .total_cmp(&nodes[**b].ucb(exploration))
+                    })
+                    .expect("There should always be a legal call"),
+            };
+            sim.apply_call(nodes[next].call.unwrap())
+                .expect("Tree calls should be legal");
+            path.push((next, actor));
+            node = next;
+            if !untried.is_empty() {
+                // Freshly expanded, so hand over to the rollout
+                break;
+            }
+        }
+        let winner = self.rollout(sim);
+        nodes[0].visits += 1;
+        for (node, actor) in path {
+            nodes[node].visits += 1;
+            if actor == winner {
+                nodes[node].wins += 1.0;
+            }
+        }
+    }
+
+    fn rollout(&mut self, mut sim: LiarsDiceGame) -> Uuid {
+        loop {
+            if let Some(winner) = sim.winner() {
+                return winner;
+            }
+            let player_id = sim.current_player().unwrap();
+            let call = self.rollout.choose_call(&sim, player_id);
+            sim.apply_call(call)
+                .expect("Rollout policy made an illegal call");
+        }
+    }
+}
+
+impl<R: Agent> Agent for IsmctsAgent<R> {
+    fn choose_call(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> DiceCall {
+        let legal = game.legal_calls();
+        if legal.len() == 1 {
+            return legal[0];
+        }
+        let start = Instant::now();
+        let mut nodes = vec![Node::new(None)];
+        for _ in 0..self.config.iterations {
+            if let Some(budget) = self.config.time_budget
+                && start.elapsed() >= budget
+            {
+                break;
+            }
+            let sim = game.determinize(&player_id, &mut self.rng);
+            self.iterate(&mut nodes, sim);
+        }
+        nodes[0]
+            .children
+            .iter()
+            .max_by_key(|child| nodes[**child].visits)
+            .and_then(|child| nodes[*child].call)
+            .unwrap_or(legal[0])
+    }
+}
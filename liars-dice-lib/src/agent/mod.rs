@@ -0,0 +1,47 @@
+mod ismcts;
+mod neural;
+mod tabular;
+
+pub use ismcts::{IsmctsAgent, IsmctsConfig};
+pub use neural::{Activation, NeuralAgent, NeuralError, NeuralNetwork, Selection};
+pub use tabular::TabularAgent;
+
+use crate::{DiceCall, LiarsDiceGame};
+use rand::seq::IndexedRandom;
+use rand_chacha::ChaCha12Rng;
+use uuid::Uuid;
+
+/// Something that can take a turn in a game.
+/// Agents are handed the full game, but should only look at what `player_id` could see:
+/// their own dice, everyone's dice counts and the calls made so far.
+pub trait Agent {
+    /// Picks a call for `player_id`, who is the current player of `game`
+    fn choose_call(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> DiceCall;
+
+    /// Why the agent can no longer play, checked after every call.
+    /// A failed agent forfeits the game, whatever call it returned.
+    fn failure(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Picks uniformly from the legal calls
+#[derive(Debug, Clone)]
+pub struct RandomAgent {
+    rng: ChaCha12Rng,
+}
+
+impl RandomAgent {
+    pub fn new(rng: ChaCha12Rng) -> Self {
+        Self { rng }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn choose_call(&mut self, game: &LiarsDiceGame, _player_id: Uuid) -> DiceCall {
+        *game
+            .legal_calls()
+            .choose(&mut self.rng)
+            .expect("Current player should always have a legal call")
+    }
+}
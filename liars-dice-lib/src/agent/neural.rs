@@ -0,0 +1,275 @@
+use crate::agent::Agent;
+use crate::encoding::{ObservationEncoder, decode_call};
+use crate::{DiceCall, LiarsDiceGame};
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand_chacha::ChaCha12Rng;
+use safetensors::{Dtype, SafeTensorError, SafeTensors};
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Describes the network stored in a safetensors file.
+/// Every layer is a `<name>.weight` of shape `[outputs, inputs]` and a `<name>.bias`, as PyTorch's `nn.Linear` saves them,
+/// with the activation between layers.
+/// Networks trained on Deep CFR samples add `"recall": true`, to take the perfect recall encoding.
+///
+/// ```json
+/// {"players": 2, "starting_dice": 5, "activation": "relu", "layers": ["fc0", "fc1", "fc2"]}
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+struct Sidecar {
+    players: usize,
+    starting_dice: u8,
+    #[serde(default)]
+    activation: Activation,
+    layers: Vec<String>,
+    #[serde(default)]
+    recall: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Activation {
+    #[default]
+    Relu,
+    Tanh,
+}
+
+#[derive(Debug)]
+pub enum NeuralError {
+    Io(std::io::Error),
+    Sidecar(serde_json::Error),
+    Weights(SafeTensorError),
+    /// The weights don't fit together, or don't fit the game described in the sidecar
+    Shape(&'static str),
+}
+
+impl Display for NeuralError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NeuralError::Io(e) => write!(f, "Could not read network: {e}"),
+            NeuralError::Sidecar(e) => write!(f, "Could not read sidecar: {e}"),
+            NeuralError::Weights(e) => write!(f, "Could not read weights: {e}"),
+            NeuralError::Shape(reason) => write!(f, "Network doesn't fit: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for NeuralError {}
+
+impl From<std::io::Error> for NeuralError {
+    fn from(e: std::io::Error) -> Self {
+        NeuralError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for NeuralError {
+    fn from(e: serde_json::Error) -> Self {
+        NeuralError::Sidecar(e)
+    }
+}
+
+impl From<SafeTensorError> for NeuralError {
+    fn from(e: SafeTensorError) -> Self {
+        NeuralError::Weights(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Dense {
+    /// Row major, one row per output
+    weight: Vec<f32>,
+    bias: Vec<f32>,
+    inputs: usize,
+}
+
+impl Dense {
+    fn forward(&self, xs: &[f32]) -> Vec<f32> {
+        self.weight
+            .chunks(self.inputs)
+            .zip(&self.bias)
+            .map(|(row, bias)| row.iter().zip(xs).map(|(w, x)| w * x).sum::<f32>() + bias)
+            .collect()
+    }
+}
+
+fn to_f32(dtype: Dtype, data: &[u8]) -> Result<Vec<f32>, NeuralError> {
+    match dtype {
+        Dtype::F32 => Ok(data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()),
+        Dtype::F64 => Ok(data
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes(b.try_into().unwrap()) as f32)
+            .collect()),
+        // bf16 is the top half of an f32
+        Dtype::BF16 => Ok(data
+            .chunks_exact(2)
+            .map(|b| f32::from_bits((u16::from_le_bytes([b[0], b[1]]) as u32) << 16))
+            .collect()),
+        _ => Err(NeuralError::Shape("Weights must be F32, F64 or BF16")),
+    }
+}
+
+/// A feed-forward network from observations to a logit per action, run on the CPU
+#[derive(Debug, Clone)]
+pub struct NeuralNetwork {
+    layers: Vec<Dense>,
+    activation: Activation,
+    encoder: ObservationEncoder,
+    /// Whether observations come from [`ObservationEncoder::encode_recall`]
+    recall: bool,
+}
+
+impl NeuralNetwork {
+    /// Loads weights from a `.safetensors` file, with the sidecar next to it under the same name ending in `.json`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NeuralError> {
+        let path = path.as_ref();
+        let sidecar = std::fs::read_to_string(path.with_extension("json"))?;
+        let weights = std::fs::read(path)?;
+        Self::from_bytes(&sidecar, &weights)
+    }
+
+    pub fn from_bytes(sidecar: &str, weights: &[u8]) -> Result<Self, NeuralError> {
+        let sidecar: Sidecar = serde_json::from_str(sidecar)?;
+        if sidecar.players < 2 || sidecar.starting_dice == 0 {
+            return Err(NeuralError::Shape(
+                "Need at least two players with at least one die each",
+            ));
+        }
+        let encoder = ObservationEncoder::new(sidecar.players, sidecar.starting_dice);
+        let tensors = SafeTensors::deserialize(weights)?;
+        let mut layers = vec![];
+        let mut inputs = match sidecar.recall {
+            true => encoder.recall_size(),
+            false => encoder.size(),
+        };
+        for name in &sidecar.layers {
+            let weight = tensors.tensor(&format!("{name}.weight"))?;
+            let bias = tensors.tensor(&format!("{name}.bias"))?;
+            let &[outputs, layer_inputs] = weight.shape() else {
+                return Err(NeuralError::Shape("Layer weights must be two dimensional"));
+            };
+            if layer_inputs != inputs {
+                return Err(NeuralError::Shape(
+                    "Layer inputs must match the size of what comes before",
+                ));
+            }
+            if bias.shape() != [outputs] {
+                return Err(NeuralError::Shape(
+                    "Layer bias must have one value per output",
+                ));
+            }
+            layers.push(Dense {
+                weight: to_f32(weight.dtype(), weight.data())?,
+                bias: to_f32(bias.dtype(), bias.data())?,
+                inputs,
+            });
+            inputs = outputs;
+        }
+        if layers.is_empty() {
+            return Err(NeuralError::Shape("Need at least one layer"));
+        }
+        if inputs != encoder.action_count() {
+            return Err(NeuralError::Shape(
+                "Last layer must have one output per action",
+            ));
+        }
+        Ok(Self {
+            layers,
+            activation: sidecar.activation,
+            encoder,
+            recall: sidecar.recall,
+        })
+    }
+
+    pub fn encoder(&self) -> &ObservationEncoder {
+        &self.encoder
+    }
+
+    pub fn logits(&self, observation: &[f32]) -> Vec<f32> {
+        let mut xs = observation.to_vec();
+        for (i, layer) in self.layers.iter().enumerate() {
+            xs = layer.forward(&xs);
+            if i + 1 < self.layers.len() {
+                for x in xs.iter_mut() {
+                    *x = match self.activation {
+                        Activation::Relu => x.max(0.0),
+                        Activation::Tanh => x.tanh(),
+                    };
+                }
+            }
+        }
+        xs
+    }
+
+    /// Probability of every action for `player_id`, with the illegal ones at zero
+    pub fn action_probs(&self, game: &LiarsDiceGame, player_id: &Uuid) -> Vec<f32> {
+        let observation = match self.recall {
+            true => self.encoder.encode_recall(game, player_id),
+            false => self.encoder.encode(game, player_id),
+        };
+        let logits = self.logits(&observation);
+        let mask = self.encoder.legal_action_mask(game);
+        let max = logits
+            .iter()
+            .zip(&mask)
+            .filter(|(_, legal)| **legal > 0.0)
+            .map(|(logit, _)| *logit)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let exps: Vec<f32> = logits
+            .iter()
+            .zip(&mask)
+            .map(|(logit, legal)| match *legal > 0.0 {
+                true => (logit - max).exp(),
+                false => 0.0,
+            })
+            .collect();
+        let total: f32 = exps.iter().sum();
+        exps.iter().map(|e| e / total).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Draws an action from the network's distribution
+    Sample,
+    /// Always takes the most likely action
+    Argmax,
+}
+
+/// Plays the calls a [`NeuralNetwork`] picks, only ever from the legal ones
+#[derive(Debug, Clone)]
+pub struct NeuralAgent {
+    network: NeuralNetwork,
+    selection: Selection,
+    rng: ChaCha12Rng,
+}
+
+impl NeuralAgent {
+    pub fn new(network: NeuralNetwork, selection: Selection, rng: ChaCha12Rng) -> Self {
+        Self {
+            network,
+            selection,
+            rng,
+        }
+    }
+}
+
+impl Agent for NeuralAgent {
+    fn choose_call(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> DiceCall {
+        let probs = self.network.action_probs(game, &player_id);
+        let action = match self.selection {
+            Selection::Argmax => (0..probs.len())
+                .max_by(|a, b| probs[*a].total_cmp(&probs[*b]))
+                .unwrap(),
+            Selection::Sample => WeightedIndex::new(&probs)
+                .expect("Current player should always have a legal call")
+                .sample(&mut self.rng),
+        };
+        decode_call(action)
+    }
+}
@@ -0,0 +1,44 @@
+use crate::agent::Agent;
+use crate::strategy::{InfoSetKey, TabularStrategy};
+use crate::{DiceCall, LiarsDiceGame};
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand::seq::IndexedRandom;
+use rand_chacha::ChaCha12Rng;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Samples calls from a [`TabularStrategy`].
+/// Information sets missing from the table, or with no legal calls in it, are played uniformly.
+#[derive(Debug, Clone)]
+pub struct TabularAgent {
+    strategy: Arc<TabularStrategy>,
+    rng: ChaCha12Rng,
+}
+
+impl TabularAgent {
+    pub fn new(strategy: Arc<TabularStrategy>, rng: ChaCha12Rng) -> Self {
+        Self { strategy, rng }
+    }
+}
+
+impl Agent for TabularAgent {
+    fn choose_call(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> DiceCall {
+        let legal = game.legal_calls();
+        let key = InfoSetKey::from_game(game, &player_id);
+        let policy: Vec<(DiceCall, f32)> = self
+            .strategy
+            .get(&key)
+            .unwrap_or_default()
+            .iter()
+            .filter(|(call, probability)| *probability > 0.0 && legal.contains(call))
+            .copied()
+            .collect();
+        match WeightedIndex::new(policy.iter().map(|(_, probability)| probability)) {
+            Ok(weights) => policy[weights.sample(&mut self.rng)].0,
+            Err(_) => *legal
+                .choose(&mut self.rng)
+                .expect("Current player should always have a legal call"),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{Dice, LiarsDiceGame};
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+use uuid::Uuid;
+
+/// Gives up on the belief after this many rejected hands and keeps the last uniform roll
+const MAX_SAMPLE_ATTEMPTS: usize = 1000;
+
+/// What an observer thinks the other players are holding, used to weight determinizations.
+/// The game handed to a belief is the observer's view of it, with every hidden hand re-rolled.
+pub trait BeliefModel {
+    /// How likely `player_id` is to hold `dice` given the game so far, from 0 to 1.
+    /// Only the ratio between hands matters, so the most likely hand should be close to 1.
+    fn likelihood(&self, game: &LiarsDiceGame, player_id: &Uuid, dice: &[Dice]) -> f64;
+
+    /// Draws a hand of `count` dice for `player_id` by rejection sampling uniform rolls
+    fn sample_hand(
+        &self,
+        game: &LiarsDiceGame,
+        player_id: &Uuid,
+        count: usize,
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Dice> {
+        let mut attempts = 0;
+        loop {
+            let hand: Vec<Dice> = (0..count).map(|_| Dice::roll(rng)).collect();
+            attempts += 1;
+            if attempts >= MAX_SAMPLE_ATTEMPTS
+                || rng.random::<f64>() < self.likelihood(game, player_id, &hand)
+            {
+                return hand;
+            }
+        }
+    }
+}
+
+/// Every hand is equally likely, which is what the dice alone tell you
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniformBelief;
+
+impl BeliefModel for UniformBelief {
+    fn likelihood(&self, _game: &LiarsDiceGame, _player_id: &Uuid, _dice: &[Dice]) -> f64 {
+        1.0
+    }
+
+    fn sample_hand(
+        &self,
+        _game: &LiarsDiceGame,
+        _player_id: &Uuid,
+        count: usize,
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Dice> {
+        (0..count).map(|_| Dice::roll(rng)).collect()
+    }
+}
@@ -0,0 +1,131 @@
+use liars_dice_lib::agent::{
+    Agent, IsmctsAgent, IsmctsConfig, NeuralAgent, NeuralNetwork, RandomAgent, Selection,
+    TabularAgent,
+};
+use liars_dice_lib::protocol::ProcessAgent;
+use liars_dice_lib::strategy::TabularStrategy;
+use liars_dice_lib::tournament::{Tournament, TournamentConfig, TournamentFormat};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::process::{Command, exit};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long an external bot gets to answer each turn
+const BOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const USAGE: &str = "usage: tournament [--format round-robin|swiss:<rounds>|king] [--games <n>] [--dice <n>] [--seed <n>] <agent>...
+agents: random, ismcts:<iterations>, neural:<weights.safetensors>, neural-argmax:<weights.safetensors>, tabular:<strategy>, process:<command line>";
+
+fn main() {
+    let mut config = TournamentConfig::default();
+    let mut agents = vec![];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| fail(&format!("{arg} needs a value")))
+        };
+        match arg.as_str() {
+            "--format" => config.format = parse_format(&value()),
+            "--games" => config.games_per_match = parse_number(&value()),
+            "--dice" => config.starting_dice = parse_number(&value()),
+            "--seed" => config.seed = parse_number(&value()),
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => agents.push(arg),
+        }
+    }
+    if agents.len() < 2 {
+        fail("Need at least two agents");
+    }
+    let starting_dice = config.starting_dice;
+    let mut tournament = Tournament::new(config);
+    for agent in agents {
+        register(&mut tournament, agent, starting_dice);
+    }
+    print!("{}", tournament.run());
+}
+
+fn register(tournament: &mut Tournament, spec: String, starting_dice: u8) {
+    match spec.split_once(':') {
+        None if spec == "random" => tournament.register(spec, |rng| {
+            Box::new(RandomAgent::new(rng)) as Box<dyn Agent>
+        }),
+        Some(("ismcts", iterations)) => {
+            let config = IsmctsConfig {
+                iterations: parse_number(iterations),
+                ..IsmctsConfig::default()
+            };
+            tournament.register(spec.clone(), move |mut rng| {
+                let rollout = RandomAgent::new(ChaCha12Rng::from_rng(&mut rng));
+                Box::new(IsmctsAgent::new(config.clone(), rng, rollout))
+            })
+        }
+        Some((kind @ ("neural" | "neural-argmax"), path)) => {
+            let network = NeuralNetwork::load(path)
+                .unwrap_or_else(|e| fail(&format!("Could not load {path}: {e}")));
+            let encoder = network.encoder();
+            if encoder.players != 2 || encoder.starting_dice != starting_dice {
+                fail(&format!(
+                    "{path} was trained for {} players with {} dice, but tournament games are heads up with {starting_dice}",
+                    encoder.players, encoder.starting_dice
+                ));
+            }
+            let selection = match kind {
+                "neural" => Selection::Sample,
+                _ => Selection::Argmax,
+            };
+            tournament.register(spec.clone(), move |rng| {
+                Box::new(NeuralAgent::new(network.clone(), selection, rng)) as Box<dyn Agent>
+            })
+        }
+        Some(("tabular", path)) => {
+            let strategy = TabularStrategy::load(path)
+                .unwrap_or_else(|e| fail(&format!("Could not load {path}: {e}")));
+            let strategy = Arc::new(strategy);
+            tournament.register(spec.clone(), move |rng| {
+                Box::new(TabularAgent::new(strategy.clone(), rng)) as Box<dyn Agent>
+            })
+        }
+        Some(("process", command_line)) => {
+            let words: Vec<String> = command_line.split_whitespace().map(String::from).collect();
+            if words.is_empty() {
+                fail("process needs a command");
+            }
+            tournament.register(spec.clone(), move |_| {
+                let mut command = Command::new(&words[0]);
+                command.args(&words[1..]);
+                match ProcessAgent::spawn(command, BOT_TIMEOUT) {
+                    Ok(agent) => Box::new(agent) as Box<dyn Agent>,
+                    Err(e) => fail(&format!("Could not start {}: {e}", words[0])),
+                }
+            })
+        }
+        _ => fail(&format!("Unknown agent {spec}")),
+    }
+}
+
+fn parse_format(format: &str) -> TournamentFormat {
+    match format.split_once(':') {
+        None if format == "round-robin" => TournamentFormat::RoundRobin,
+        None if format == "king" => TournamentFormat::KingOfTheHill,
+        Some(("swiss", rounds)) => TournamentFormat::Swiss {
+            rounds: parse_number(rounds),
+        },
+        _ => fail(&format!("Unknown format {format}")),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("{value} is not a valid number")))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    exit(1)
+}
@@ -0,0 +1,278 @@
+//! Sample collection for Deep CFR, using external sampling MCCFR over the engine.
+//!
+//! Each traversal plays out the round in progress from the traverser's point of view:
+//! every legal call is tried at the traverser's turns, while other players and the dice are sampled.
+//! The round ends at the first challenge, where whoever lost a die gets -1 and the rest share +1.
+//! Regrets at the traverser's turns go into a reservoir buffer per seat, to train advantage networks,
+//! and the strategy at everyone else's turns goes into a shared buffer, to train the average strategy.
+//! Samples use [`ObservationEncoder::encode_recall`], since CFR needs every earlier bid in the round
+//! to tell information sets apart.
+//! Buffers are saved as `.npy` files, ready for `numpy.load`.
+mod npy;
+mod reservoir;
+
+pub use reservoir::ReservoirBuffer;
+
+use crate::LiarsDiceGame;
+use crate::agent::NeuralNetwork;
+use crate::encoding::{ObservationEncoder, decode_call};
+use crate::state::LiarsDiceState;
+use rand::SeedableRng;
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand_chacha::ChaCha12Rng;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Predicts how much better each action is than the current strategy, for a player in `seat`
+pub trait AdvantageModel {
+    fn advantages(&self, seat: usize, observation: &[f32]) -> Vec<f32>;
+}
+
+/// Predicts nothing, so regret matching plays uniformly, which is where Deep CFR starts
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZeroAdvantages;
+
+impl AdvantageModel for ZeroAdvantages {
+    fn advantages(&self, _seat: usize, _observation: &[f32]) -> Vec<f32> {
+        vec![]
+    }
+}
+
+/// An advantage network trained elsewhere, shared by every seat.
+/// It should have been saved with `"recall": true`, to take the samples' observations.
+impl AdvantageModel for NeuralNetwork {
+    fn advantages(&self, _seat: usize, observation: &[f32]) -> Vec<f32> {
+        self.logits(observation)
+    }
+}
+
+/// Plays legal actions in proportion to their positive advantage, or uniformly when none is positive.
+/// Missing advantages count as zero.
+pub fn regret_matching(advantages: &[f32], legal_mask: &[f32]) -> Vec<f32> {
+    let positive: Vec<f32> = legal_mask
+        .iter()
+        .enumerate()
+        .map(|(action, legal)| match *legal > 0.0 {
+            true => advantages.get(action).copied().unwrap_or(0.0).max(0.0),
+            false => 0.0,
+        })
+        .collect();
+    let total: f32 = positive.iter().sum();
+    match total > 0.0 {
+        true => positive.iter().map(|p| p / total).collect(),
+        false => {
+            let legal: f32 = legal_mask.iter().sum();
+            legal_mask.iter().map(|l| l / legal).collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfrSample {
+    pub observation: Vec<f32>,
+    pub legal_mask: Vec<f32>,
+    /// Regrets for advantage samples, or the strategy played for strategy samples
+    pub target: Vec<f32>,
+    /// CFR iteration the sample came from, for weighting as in linear CFR
+    pub iteration: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeepCfrConfig {
+    pub players: usize,
+    pub starting_dice: u8,
+    /// Samples kept in each seat's advantage buffer
+    pub advantage_capacity: usize,
+    pub strategy_capacity: usize,
+    pub seed: u64,
+}
+
+impl Default for DeepCfrConfig {
+    fn default() -> Self {
+        Self {
+            players: 2,
+            starting_dice: 2,
+            advantage_capacity: 1_000_000,
+            strategy_capacity: 1_000_000,
+            seed: 0,
+        }
+    }
+}
+
+pub struct DeepCfr {
+    config: DeepCfrConfig,
+    encoder: ObservationEncoder,
+    /// One per seat, in player id order
+    advantages: Vec<ReservoirBuffer<CfrSample>>,
+    strategies: ReservoirBuffer<CfrSample>,
+    rng: ChaCha12Rng,
+}
+
+impl DeepCfr {
+    pub fn new(config: DeepCfrConfig) -> Self {
+        assert!(config.players > 1 && config.starting_dice > 0);
+        Self {
+            encoder: ObservationEncoder::new(config.players, config.starting_dice),
+            advantages: (0..config.players)
+                .map(|_| ReservoirBuffer::new(config.advantage_capacity))
+                .collect(),
+            strategies: ReservoirBuffer::new(config.strategy_capacity),
+            rng: ChaCha12Rng::seed_from_u64(config.seed),
+            config,
+        }
+    }
+
+    pub fn encoder(&self) -> &ObservationEncoder {
+        &self.encoder
+    }
+
+    pub fn advantages(&self, seat: usize) -> &ReservoirBuffer<CfrSample> {
+        &self.advantages[seat]
+    }
+
+    pub fn strategies(&self) -> &ReservoirBuffer<CfrSample> {
+        &self.strategies
+    }
+
+    /// Runs `traversals` traversals for every seat, each over the first round of a freshly dealt game.
+    /// `model` should be the advantage networks trained on the buffers after the previous iteration.
+    pub fn run_iteration(
+        &mut self,
+        iteration: u32,
+        traversals: usize,
+        model: &impl AdvantageModel,
+    ) {
+        let seats: Vec<Uuid> = (0..self.config.players)
+            .map(|i| Uuid::from_u128(i as u128 + 1))
+            .collect();
+        for traverser in &seats {
+            for _ in 0..traversals {
+                let rng = ChaCha12Rng::from_rng(&mut self.rng);
+                let mut game = LiarsDiceGame::new(rng, self.config.starting_dice, &seats);
+                if let LiarsDiceState::Start(start) = game.get_state() {
+                    start.initialise_game();
+                }
+                self.traverse(&game, traverser, iteration, model);
+            }
+        }
+    }
+
+    /// Traverses the rest of the round in progress, returning what it is worth to `traverser`.
+    /// The game should be heading for a player's turn, not waiting on explicit chance.
+    pub fn traverse(
+        &mut self,
+        game: &LiarsDiceGame,
+        traverser: &Uuid,
+        iteration: u32,
+        model: &impl AdvantageModel,
+    ) -> f32 {
+        let player_id = game.current_player().expect("Game should have started");
+        let seat = game
+            .player_dices()
+            .keys()
+            .position(|id| *id == player_id)
+            .unwrap();
+        let observation = self.encoder.encode_recall(game, &player_id);
+        let legal_mask = self.encoder.legal_action_mask(game);
+        let strategy = regret_matching(&model.advantages(seat, &observation), &legal_mask);
+        if player_id != *traverser {
+            let action = WeightedIndex::new(&strategy)
+                .expect("Current player should always have a legal call")
+                .sample(&mut self.rng);
+            let sample = CfrSample {
+                observation,
+                legal_mask,
+                target: strategy,
+                iteration,
+            };
+            self.strategies.push(sample, &mut self.rng);
+            return self.action_value(game, action, traverser, iteration, model);
+        }
+        let mut values = vec![0.0; legal_mask.len()];
+        for (action, legal) in legal_mask.iter().enumerate() {
+            if *legal > 0.0 {
+                values[action] = self.action_value(game, action, traverser, iteration, model);
+            }
+        }
+        let expected: f32 = values.iter().zip(&strategy).map(|(v, p)| v * p).sum();
+        let regrets = values
+            .iter()
+            .zip(&legal_mask)
+            .map(|(value, legal)| match *legal > 0.0 {
+                true => value - expected,
+                false => 0.0,
+            })
+            .collect();
+        let sample = CfrSample {
+            observation,
+            legal_mask,
+            target: regrets,
+            iteration,
+        };
+        self.advantages[seat].push(sample, &mut self.rng);
+        expected
+    }
+
+    fn action_value(
+        &mut self,
+        game: &LiarsDiceGame,
+        action: usize,
+        traverser: &Uuid,
+        iteration: u32,
+        model: &impl AdvantageModel,
+    ) -> f32 {
+        let mut child = game.clone();
+        let before = child.player_dices();
+        let outcome = child
+            .apply_call(decode_call(action))
+            .expect("Legal actions should apply");
+        if outcome.is_none() {
+            return self.traverse(&child, traverser, iteration, model);
+        }
+        let after = child.player_dices();
+        let lost = |id: &Uuid| after[id].len() < before[id].len();
+        let in_round: Vec<&Uuid> = before.keys().filter(|id| !before[*id].is_empty()).collect();
+        let losers = in_round.iter().filter(|id| lost(id)).count();
+        match lost(traverser) {
+            true => -1.0,
+            false => losers as f32 / (in_round.len() - losers) as f32,
+        }
+    }
+
+    /// Writes every buffer to `dir` as `.npy` files:
+    /// `advantages_<seat>_*.npy` for each seat and `strategies_*.npy`, where `*` is
+    /// `observations`, `masks`, `targets` and `iterations`
+    pub fn save(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        for (seat, buffer) in self.advantages.iter().enumerate() {
+            self.save_samples(buffer.items(), dir, &format!("advantages_{seat}"))?;
+        }
+        self.save_samples(self.strategies.items(), dir, "strategies")
+    }
+
+    fn save_samples(&self, samples: &[CfrSample], dir: &Path, name: &str) -> std::io::Result<()> {
+        let rows = samples.len();
+        let (size, actions) = (self.encoder.recall_size(), self.encoder.action_count());
+        let flatten = |field: fn(&CfrSample) -> &Vec<f32>| -> Vec<f32> {
+            samples
+                .iter()
+                .flat_map(|s| field(s).iter().copied())
+                .collect()
+        };
+        let path = |field: &str| dir.join(format!("{name}_{field}.npy"));
+        npy::write_f32(
+            &path("observations"),
+            &[rows, size],
+            &flatten(|s| &s.observation),
+        )?;
+        npy::write_f32(
+            &path("masks"),
+            &[rows, actions],
+            &flatten(|s| &s.legal_mask),
+        )?;
+        npy::write_f32(&path("targets"), &[rows, actions], &flatten(|s| &s.target))?;
+        let iterations: Vec<u32> = samples.iter().map(|s| s.iteration).collect();
+        npy::write_u32(&path("iterations"), &[rows], &iterations)
+    }
+}
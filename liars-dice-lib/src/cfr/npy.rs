@@ -0,0 +1,32 @@
+//! Just enough of the NumPy `.npy` format to write flat little endian arrays.
+use std::io::Write;
+use std::path::Path;
+
+pub(crate) fn write_f32(path: &Path, shape: &[usize], values: &[f32]) -> std::io::Result<()> {
+    let data: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    write(path, "<f4", shape, &data)
+}
+
+pub(crate) fn write_u32(path: &Path, shape: &[usize], values: &[u32]) -> std::io::Result<()> {
+    let data: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    write(path, "<u4", shape, &data)
+}
+
+fn write(path: &Path, descr: &str, shape: &[usize], data: &[u8]) -> std::io::Result<()> {
+    let dims: Vec<String> = shape.iter().map(|d| d.to_string()).collect();
+    let shape = match dims.as_slice() {
+        [only] => format!("({only},)"),
+        _ => format!("({})", dims.join(", ")),
+    };
+    let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+    // Magic, version and header length take 10 bytes, and the data has to start 64 byte aligned
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    file.write_all(b"\x93NUMPY\x01\x00")?;
+    file.write_all(&(header.len() as u16).to_le_bytes())?;
+    file.write_all(header.as_bytes())?;
+    file.write_all(data)?;
+    file.flush()
+}
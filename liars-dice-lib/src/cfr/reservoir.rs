@@ -0,0 +1,55 @@
+use rand::Rng;
+
+/// Keeps a uniform sample of everything ever pushed, in a fixed amount of memory
+#[derive(Debug, Clone)]
+pub struct ReservoirBuffer<T> {
+    capacity: usize,
+    /// How many items have been pushed, including the ones that were dropped
+    seen: u64,
+    items: Vec<T>,
+}
+
+impl<T> ReservoirBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self {
+            capacity,
+            seen: 0,
+            items: vec![],
+        }
+    }
+
+    /// Every item seen so far has the same chance of being kept
+    pub fn push(&mut self, item: T, rng: &mut impl Rng) {
+        self.seen += 1;
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+            return;
+        }
+        let slot = rng.random_range(0..self.seen);
+        if slot < self.capacity as u64 {
+            self.items[slot as usize] = item;
+        }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    pub fn clear(&mut self) {
+        self.seen = 0;
+        self.items.clear();
+    }
+}
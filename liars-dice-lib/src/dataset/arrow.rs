@@ -0,0 +1,103 @@
+use crate::dataset::{DecisionRow, RowWriter};
+use crate::encoding::ObservationEncoder;
+use arrow_array::{
+    ArrayRef, BooleanArray, FixedSizeListArray, Float32Array, RecordBatch, StringArray,
+    UInt32Array, UInt64Array,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use std::io::Write;
+use std::sync::Arc;
+
+/// Writes rows as an Arrow IPC file, one record batch per game
+pub struct ArrowIpcWriter<W: Write> {
+    writer: FileWriter<W>,
+    schema: SchemaRef,
+    observation_size: i32,
+    action_count: i32,
+}
+
+fn vector(size: i32) -> DataType {
+    DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, false)), size)
+}
+
+impl<W: Write> ArrowIpcWriter<W> {
+    pub fn new(out: W, encoder: &ObservationEncoder) -> Result<Self, ArrowError> {
+        let observation_size = encoder.size() as i32;
+        let action_count = encoder.action_count() as i32;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("game", DataType::UInt64, false),
+            Field::new("turn", DataType::UInt32, false),
+            Field::new("round", DataType::UInt32, false),
+            Field::new("player", DataType::UInt32, false),
+            Field::new("observation", vector(observation_size), false),
+            Field::new("legal_mask", vector(action_count), false),
+            Field::new("action", DataType::UInt32, false),
+            Field::new("call", DataType::Utf8, false),
+            Field::new("correct_call", DataType::Boolean, true),
+            Field::new("reward", DataType::Float32, false),
+            Field::new("final_return", DataType::Float32, false),
+        ]));
+        Ok(Self {
+            writer: FileWriter::try_new(out, &schema)?,
+            schema,
+            observation_size,
+            action_count,
+        })
+    }
+
+    pub fn into_inner(self) -> Result<W, ArrowError> {
+        self.writer.into_inner()
+    }
+
+    fn batch(&self, rows: &[DecisionRow]) -> Result<RecordBatch, ArrowError> {
+        let vectors = |size: i32, values: Vec<f32>| -> Result<ArrayRef, ArrowError> {
+            let field = Arc::new(Field::new("item", DataType::Float32, false));
+            let values = Arc::new(Float32Array::from(values));
+            Ok(Arc::new(FixedSizeListArray::try_new(
+                field, size, values, None,
+            )?))
+        };
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from_iter_values(rows.iter().map(|r| r.game))),
+            Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.turn))),
+            Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.round))),
+            Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.player))),
+            vectors(
+                self.observation_size,
+                rows.iter()
+                    .flat_map(|r| r.observation.iter().copied())
+                    .collect(),
+            )?,
+            vectors(
+                self.action_count,
+                rows.iter()
+                    .flat_map(|r| r.legal_mask.iter().copied())
+                    .collect(),
+            )?,
+            Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.action))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.call))),
+            Arc::new(BooleanArray::from(
+                rows.iter().map(|r| r.correct_call).collect::<Vec<_>>(),
+            )),
+            Arc::new(Float32Array::from_iter_values(
+                rows.iter().map(|r| r.reward),
+            )),
+            Arc::new(Float32Array::from_iter_values(
+                rows.iter().map(|r| r.final_return),
+            )),
+        ];
+        RecordBatch::try_new(self.schema.clone(), columns)
+    }
+}
+
+impl<W: Write> RowWriter for ArrowIpcWriter<W> {
+    fn write_rows(&mut self, rows: &[DecisionRow]) -> std::io::Result<()> {
+        let batch = self.batch(rows).map_err(std::io::Error::other)?;
+        self.writer.write(&batch).map_err(std::io::Error::other)
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.writer.finish().map_err(std::io::Error::other)
+    }
+}
@@ -0,0 +1,190 @@
+//! Self-play games recorded as one row per decision, for offline RL and behaviour cloning.
+#[cfg(feature = "arrow")]
+mod arrow;
+
+#[cfg(feature = "arrow")]
+pub use arrow::ArrowIpcWriter;
+
+use crate::agent::Agent;
+use crate::encoding::{ObservationEncoder, encode_call};
+use crate::protocol::format_call;
+use crate::{DiceCall, LiarsDiceGame, LiarsDiceState, RuleVariant};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecisionRow {
+    /// Which game of the dataset the decision was made in
+    pub game: u64,
+    /// Decisions made earlier in the same game
+    pub turn: u32,
+    /// Rounds finished earlier in the same game
+    pub round: u32,
+    /// Seat of the acting player, counting players in id order
+    pub player: u32,
+    pub observation: Vec<f32>,
+    pub legal_mask: Vec<f32>,
+    pub action: u32,
+    /// The call as written in the bot protocol
+    pub call: String,
+    /// Whether a challenge was right, only set for challenges
+    pub correct_call: Option<bool>,
+    /// 1 for a correct challenge, -1 for a wrong one and 0 for a raise
+    pub reward: f32,
+    /// 1 if the acting player went on to win the game, -1 otherwise
+    pub final_return: f32,
+}
+
+pub trait RowWriter {
+    fn write_rows(&mut self, rows: &[DecisionRow]) -> std::io::Result<()>;
+
+    /// Flushes anything buffered, and writes whatever footer the format needs
+    fn finish(&mut self) -> std::io::Result<()>;
+}
+
+/// One JSON object per line
+pub struct JsonlWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonlWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> RowWriter for JsonlWriter<W> {
+    fn write_rows(&mut self, rows: &[DecisionRow]) -> std::io::Result<()> {
+        for row in rows {
+            serde_json::to_writer(&mut self.out, row)?;
+            writeln!(self.out)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SelfPlayConfig {
+    pub games: u64,
+    pub players: usize,
+    pub starting_dice: u8,
+    pub rules: RuleVariant,
+    /// Every game gets its own stream of this seed
+    pub seed: u64,
+}
+
+impl SelfPlayConfig {
+    /// The encoder rows will be written with, for writers that need the sizes up front
+    pub fn encoder(&self) -> ObservationEncoder {
+        ObservationEncoder::new(self.players, self.starting_dice)
+    }
+}
+
+/// Plays a game to the end with the given agents, returning a row for every decision.
+/// An agent making an illegal call or failing stops the game, like [`crate::tournament::play_game`].
+pub fn record_game(
+    game_index: u64,
+    game: &mut LiarsDiceGame,
+    agents: &mut BTreeMap<Uuid, Box<dyn Agent>>,
+) -> Result<Vec<DecisionRow>, (Uuid, &'static str)> {
+    let seats: Vec<Uuid> = game.player_dices().keys().copied().collect();
+    let encoder = ObservationEncoder::new(seats.len(), game.starting_dice());
+    let mut rows = vec![];
+    let mut actors = vec![];
+    let mut round = 0;
+    let winner = loop {
+        let player_id = match game.get_state() {
+            LiarsDiceState::Start(start) => {
+                start.initialise_game();
+                continue;
+            }
+            LiarsDiceState::Chance(chance) => {
+                chance.sample();
+                continue;
+            }
+            LiarsDiceState::GameComplete(complete) => break complete.winner_id,
+            LiarsDiceState::PlayerTurn(turn) => turn.player_id,
+        };
+        let observation = encoder.encode(game, &player_id);
+        let legal_mask = encoder.legal_action_mask(game);
+        let agent = agents
+            .get_mut(&player_id)
+            .expect("Every player should have an agent");
+        let call = agent.choose_call(game, player_id);
+        if let Some(reason) = agent.failure() {
+            return Err((player_id, reason));
+        }
+        let outcome = game.apply_call(call).map_err(|e| (player_id, e))?;
+        let correct_call = outcome.map(|outcome| outcome.correct_call);
+        rows.push(DecisionRow {
+            game: game_index,
+            turn: rows.len() as u32,
+            round,
+            player: seats.iter().position(|id| *id == player_id).unwrap() as u32,
+            observation,
+            legal_mask,
+            action: encode_call(&call) as u32,
+            call: format_call(&call),
+            correct_call,
+            reward: match (call, correct_call) {
+                (DiceCall::Increase { .. }, _) => 0.0,
+                (_, Some(true)) => 1.0,
+                _ => -1.0,
+            },
+            final_return: 0.0,
+        });
+        actors.push(player_id);
+        if correct_call.is_some() {
+            round += 1;
+        }
+    };
+    for (row, actor) in rows.iter_mut().zip(actors) {
+        row.final_return = match actor == winner {
+            true => 1.0,
+            false => -1.0,
+        };
+    }
+    Ok(rows)
+}
+
+/// Plays games where every seat is taken by an agent from `factory`, streaming the rows to `writer`.
+/// Returns how many rows were written.
+pub fn self_play(
+    config: &SelfPlayConfig,
+    factory: impl Fn(ChaCha12Rng) -> Box<dyn Agent>,
+    writer: &mut impl RowWriter,
+) -> std::io::Result<usize> {
+    let seats: Vec<Uuid> = (0..config.players)
+        .map(|i| Uuid::from_u128(i as u128 + 1))
+        .collect();
+    let mut written = 0;
+    for game_index in 0..config.games {
+        let mut rng = ChaCha12Rng::seed_from_u64(config.seed);
+        rng.set_stream(game_index);
+        let mut agents: BTreeMap<Uuid, Box<dyn Agent>> = seats
+            .iter()
+            .map(|seat| (*seat, factory(ChaCha12Rng::from_rng(&mut rng))))
+            .collect();
+        let mut game =
+            LiarsDiceGame::new(rng, config.starting_dice, &seats).with_rules(config.rules);
+        let rows = record_game(game_index, &mut game, &mut agents).map_err(|(player_id, e)| {
+            std::io::Error::other(format!("{player_id} forfeited: {e}"))
+        })?;
+        writer.write_rows(&rows)?;
+        written += rows.len();
+    }
+    writer.finish()?;
+    Ok(written)
+}
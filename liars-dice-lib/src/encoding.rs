@@ -0,0 +1,136 @@
+//! Fixed size numeric views of the game, for training and running neural agents.
+//!
+//! Actions are numbered `0` for Bullshit, `1` for SpotOn, then every raise in order of count and face,
+//! so `2 + (count - 1) * 6 + (face - 1)` for a raise.
+use crate::{Dice, DiceCall, LiarsDiceGame};
+use uuid::Uuid;
+
+/// Values describing a single call: whether there is one, its count as a share of all dice and its face
+const CALL_BLOCK: usize = 8;
+
+pub fn action_count(total_dice: usize) -> usize {
+    2 + total_dice * 6
+}
+
+pub fn encode_call(call: &DiceCall) -> usize {
+    match call {
+        DiceCall::Bullshit => 0,
+        DiceCall::SpotOn => 1,
+        DiceCall::Increase { count, value } => 2 + (count - 1) * 6 + (value.0 as usize - 1),
+    }
+}
+
+pub fn decode_call(action: usize) -> DiceCall {
+    match action {
+        0 => DiceCall::Bullshit,
+        1 => DiceCall::SpotOn,
+        raise => DiceCall::Increase {
+            count: (raise - 2) / 6 + 1,
+            value: Dice(((raise - 2) % 6 + 1) as u8),
+        },
+    }
+}
+
+/// Turns what a player can see into a flat vector of floats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObservationEncoder {
+    pub players: usize,
+    pub starting_dice: u8,
+}
+
+impl ObservationEncoder {
+    pub fn new(players: usize, starting_dice: u8) -> Self {
+        Self {
+            players,
+            starting_dice,
+        }
+    }
+
+    pub fn total_dice(&self) -> usize {
+        self.players * self.starting_dice as usize
+    }
+
+    pub fn action_count(&self) -> usize {
+        action_count(self.total_dice())
+    }
+
+    /// Own face counts, then every seat's dice count and last call starting from the observer,
+    /// then the bid on the table
+    pub fn size(&self) -> usize {
+        6 + self.players + self.players * CALL_BLOCK + CALL_BLOCK
+    }
+
+    pub fn encode(&self, game: &LiarsDiceGame, observer: &Uuid) -> Vec<f32> {
+        let mut out = vec![0.0; self.size()];
+        self.encode_into(game, observer, &mut out);
+        out
+    }
+
+    pub fn encode_into(&self, game: &LiarsDiceGame, observer: &Uuid, out: &mut [f32]) {
+        assert_eq!(out.len(), self.size());
+        out.fill(0.0);
+        let starting_dice = self.starting_dice as f32;
+        let player_dices = game.player_dices();
+        for d in &player_dices[observer] {
+            out[d.0 as usize - 1] += 1.0 / starting_dice;
+        }
+        // Seats are listed from the observer onwards, so the encoding doesn't depend on where they sit
+        let mut seats = game.seating().to_vec();
+        let observer_seat = seats.iter().position(|id| id == observer).unwrap();
+        seats.rotate_left(observer_seat);
+        let calls_start = 6 + self.players;
+        for (seat, id) in seats.iter().enumerate() {
+            out[6 + seat] = player_dices[id].len() as f32 / starting_dice;
+            let block = calls_start + seat * CALL_BLOCK;
+            self.encode_call_block(
+                &game.players[id].last_call,
+                &mut out[block..block + CALL_BLOCK],
+            );
+        }
+        let bid = match game.current_player() {
+            Some(_) if !game.is_complete() => game.previous_calls()[0].2,
+            _ => None,
+        };
+        let block = calls_start + self.players * CALL_BLOCK;
+        self.encode_call_block(&bid, &mut out[block..]);
+    }
+
+    /// Only raises are ever left on the table, as challenges end the round
+    fn encode_call_block(&self, call: &Option<DiceCall>, out: &mut [f32]) {
+        if let Some(DiceCall::Increase { count, value }) = call {
+            out[0] = 1.0;
+            out[1] = *count as f32 / self.total_dice() as f32;
+            out[1 + value.0 as usize] = 1.0;
+        }
+    }
+
+    /// [`Self::size`], then a bit per raise made so far in the round.
+    /// Raises only ever go up, so the bits give the round's whole bid sequence,
+    /// which keeps information sets with different histories apart.
+    pub fn recall_size(&self) -> usize {
+        self.size() + self.total_dice() * 6
+    }
+
+    /// [`Self::encode`] with perfect recall of the round's bids, as laid out in [`Self::recall_size`]
+    pub fn encode_recall(&self, game: &LiarsDiceGame, observer: &Uuid) -> Vec<f32> {
+        let mut out = vec![0.0; self.recall_size()];
+        let size = self.size();
+        self.encode_into(game, observer, &mut out[..size]);
+        for call in game.round_calls() {
+            if let DiceCall::Increase { .. } = call {
+                // Raises are numbered from 2, after Bullshit and SpotOn
+                out[size + encode_call(&call) - 2] = 1.0;
+            }
+        }
+        out
+    }
+
+    /// 1 for each action the current player may take, 0 otherwise
+    pub fn legal_action_mask(&self, game: &LiarsDiceGame) -> Vec<f32> {
+        let mut mask = vec![0.0; self.action_count()];
+        for call in game.legal_calls() {
+            mask[encode_call(&call)] = 1.0;
+        }
+        mask
+    }
+}
@@ -1,25 +1,50 @@
+pub mod agent;
+pub mod belief;
+pub mod cfr;
+pub mod dataset;
+pub mod encoding;
+pub mod openspiel;
+pub mod profile;
+pub mod protocol;
+#[cfg(feature = "python")]
+mod python;
+pub mod record;
+pub mod scenario;
 mod state;
+pub mod strategy;
 #[cfg(test)]
 mod test;
+pub mod tournament;
 
-use crate::state::{GameCompleteState, GameStartState, LiarsDiceState, PlayerTurnState};
+use crate::belief::{BeliefModel, UniformBelief};
+pub use crate::state::{
+    ChanceNode, ConfirmChoice, GameCompleteState, GameStartState, LiarsDiceState, PlayerTurnState,
+};
 use rand::seq::IndexedRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha12Rng;
 use std::collections::BTreeMap;
 use uuid::Uuid;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LiarsDiceGame {
     rng: ChaCha12Rng,
     starting_dice: u8,
     original_player_order: Vec<Uuid>,
+    /// Everyone in the order turns go round, including anyone knocked out
+    seating: Vec<Uuid>,
     current_players: Vec<Uuid>,
     current_player: Option<Uuid>,
     players: BTreeMap<Uuid, LiarsDicePlayer>,
+    history: Vec<GameEvent>,
+    rules: RuleVariant,
+    /// Leave dice rolls to the caller as chance nodes instead of rolling them straight away
+    explicit_chance: bool,
+    /// Dice waiting on a chance outcome, by player and position in their hand
+    pending_rolls: Vec<(Uuid, usize)>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LiarsDicePlayer {
     pub id: Uuid,
     pub dice: Vec<Dice>,
@@ -28,7 +53,7 @@ pub struct LiarsDicePlayer {
 
 impl LiarsDiceGame {
     pub fn new(mut rng: ChaCha12Rng, starting_dice: u8, player_ids: &[Uuid]) -> Self {
-        let original_player_order: Vec<Uuid> = player_ids.iter().cloned().collect();
+        let original_player_order: Vec<Uuid> = player_ids.to_vec();
         let mut players = BTreeMap::new();
         for p in player_ids {
             players.insert(
@@ -47,33 +72,115 @@ impl LiarsDiceGame {
             original_player_order,
             rng,
             starting_dice,
+            // Initialising the game seats everyone in id order
+            seating: players.keys().copied().collect(),
             players,
             current_player: None,
             current_players: vec![],
+            history: vec![],
+            rules: RuleVariant::default(),
+            explicit_chance: false,
+            pending_rolls: vec![],
         }
     }
 
+    pub fn with_rules(mut self, rules: RuleVariant) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn starting_dice(&self) -> u8 {
+        self.starting_dice
+    }
+
+    pub fn rules(&self) -> RuleVariant {
+        self.rules
+    }
+
+    /// Surfaces every die roll after the game starts as a [`ChanceNode`], to be resolved by the caller
+    pub fn with_explicit_chance(mut self, explicit_chance: bool) -> Self {
+        self.explicit_chance = explicit_chance;
+        self
+    }
+
     /// All players get their dice back
     pub fn return_all_dice_for_new_game(&mut self) {
         for (_, player) in self.players.iter_mut() {
-            player.dice.clear();
-            player
-                .dice
-                .extend((0..self.starting_dice).map(|_| Dice::roll(&mut self.rng)));
+            player.dice = vec![Dice(1); self.starting_dice as usize];
             player.last_call = None;
         }
-        self.current_players = self.original_player_order.clone();
+        self.history.clear();
+        self.pending_rolls.clear();
+        let player_ids: Vec<Uuid> = self.players.keys().copied().collect();
+        self.roll_dice(&player_ids);
+        self.seating = self.original_player_order.clone();
+        self.current_players = self.seating.clone();
         self.current_player = self.current_players.choose(&mut self.rng).cloned();
     }
 
     pub fn start_next_round(&mut self) {
         for id in &self.current_players {
             let player = self.players.get_mut(id).unwrap();
-            for d in player.dice.iter_mut() {
-                *d = Dice::roll(&mut self.rng);
-            }
             player.last_call = None
         }
+        if !self.is_complete() {
+            self.roll_dice(&self.current_players.clone());
+        }
+    }
+
+    /// Rolls every die the players hold, or queues them up as chance nodes when chance is explicit.
+    /// The round goes into the history once every die has landed.
+    pub(crate) fn roll_dice(&mut self, player_ids: &[Uuid]) {
+        for id in player_ids {
+            let player = self.players.get_mut(id).unwrap();
+            for (index, d) in player.dice.iter_mut().enumerate() {
+                match self.explicit_chance {
+                    true => self.pending_rolls.push((*id, index)),
+                    false => *d = Dice::roll(&mut self.rng),
+                }
+            }
+        }
+        if self.pending_rolls.is_empty() {
+            self.record_round_start();
+        }
+    }
+
+    pub(crate) fn resolve_roll(&mut self, outcome: Dice) {
+        let (player_id, index) = self.pending_rolls.remove(0);
+        self.players.get_mut(&player_id).unwrap().dice[index] = outcome;
+        if self.pending_rolls.is_empty() {
+            self.record_round_start();
+        }
+    }
+
+    fn record_round_start(&mut self) {
+        let player_dices = self
+            .player_dices()
+            .into_iter()
+            .filter(|(_, dice)| !dice.is_empty())
+            .collect();
+        self.history.push(GameEvent::RoundStarted { player_dices });
+    }
+
+    /// Everything that happened since the game was initialised, oldest first
+    pub fn history(&self) -> &[GameEvent] {
+        &self.history
+    }
+
+    /// Every call made since the round in progress was dealt, oldest first
+    pub fn round_calls(&self) -> Vec<DiceCall> {
+        let round_start = self
+            .history
+            .iter()
+            .rposition(|event| matches!(event, GameEvent::RoundStarted { .. }))
+            .map_or(0, |index| index + 1);
+        self.history[round_start..]
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::Called { call, .. } => Some(*call),
+                _ => None,
+            })
+            .collect()
     }
 
     /// Returns the calls of the previous players, ordered by recency
@@ -127,33 +234,186 @@ impl LiarsDiceGame {
     }
 
     pub fn rotate_player(&mut self) {
-        let player_id = self.current_player.as_ref().unwrap().clone();
-        let (index, _) = self
+        let player_id = self.current_player.unwrap();
+        let index = self
             .current_players
             .iter()
-            .enumerate()
-            .filter(|(_, id)| *id == &player_id)
-            .next()
+            .position(|id| *id == player_id)
             .unwrap();
         let next_index = (index + 1) % self.current_players.len();
         let player_id = self.current_players[next_index];
         self.current_player = Some(player_id);
     }
 
+    pub fn current_player(&self) -> Option<Uuid> {
+        self.current_player
+    }
+
+    /// Every player in seating order, including anyone already knocked out
+    pub fn seating(&self) -> &[Uuid] {
+        &self.seating
+    }
+
+    /// Players still holding dice, in seating order
+    pub fn current_players(&self) -> &[Uuid] {
+        &self.current_players
+    }
+
+    /// The last player holding dice, once everyone else has been knocked out
+    pub fn winner(&self) -> Option<Uuid> {
+        let mut with_dice = self.players.values().filter(|p| !p.dice.is_empty());
+        match (with_dice.next(), with_dice.next()) {
+            (Some(player), None) => Some(player.id),
+            _ => None,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    /// Every call the current player is allowed to make.
+    /// Raises are capped at the number of dice still in play, as anything higher can never be true.
+    pub fn legal_calls(&self) -> Vec<DiceCall> {
+        if self.current_player.is_none() || self.is_complete() || !self.pending_rolls.is_empty() {
+            return vec![];
+        }
+        let total_dice: usize = self
+            .current_players
+            .iter()
+            .map(|id| self.players[id].dice.len())
+            .sum();
+        let previous = self.previous_calls().first().and_then(|(_, _, call)| *call);
+        let (mut calls, previous_bid) = match previous {
+            None => (vec![], None),
+            Some(DiceCall::Increase { count, value }) => match self.rules.allows_spot_on() {
+                true => (
+                    vec![DiceCall::Bullshit, DiceCall::SpotOn],
+                    Some((count, value)),
+                ),
+                false => (vec![DiceCall::Bullshit], Some((count, value))),
+            },
+            Some(_) => unreachable!("Previous call should have been final"),
+        };
+        let min_count = previous_bid.map_or(1, |(count, _)| count);
+        for count in min_count..=total_dice {
+            for value in 1..=6 {
+                let bid = (count, Dice(value));
+                if previous_bid.is_none_or(|previous| self.rules.is_raise(previous, bid)) {
+                    calls.push(DiceCall::Increase {
+                        count,
+                        value: Dice(value),
+                    });
+                }
+            }
+        }
+        calls
+    }
+
+    /// Makes a call on behalf of the current player, going through the propose and confirm steps
+    pub fn apply_call(&mut self, call: DiceCall) -> Result<Option<CallOutcome>, &'static str> {
+        match self.get_state() {
+            LiarsDiceState::PlayerTurn(turn) => {
+                let choice = turn.propose_choice(call)?;
+                Ok(choice.confirm(turn))
+            }
+            LiarsDiceState::Start(_) => Err("Game has not been initialised"),
+            LiarsDiceState::Chance(_) => Err("Dice still need to be rolled"),
+            LiarsDiceState::GameComplete(_) => Err("Game is already complete"),
+        }
+    }
+
+    /// A copy of the game as `observer` could imagine it: their own dice, every dice count, the calls and
+    /// the seating are kept, while everyone else's dice are re-rolled.
+    /// The embedded rng is reseeded too, so future rolls can't be read off the copy either.
+    pub fn determinize(&self, observer: &Uuid, rng: &mut ChaCha12Rng) -> LiarsDiceGame {
+        self.determinize_with(observer, &UniformBelief, rng)
+    }
+
+    /// Same as [`LiarsDiceGame::determinize`], but hidden hands are drawn in proportion to `belief`
+    pub fn determinize_with(
+        &self,
+        observer: &Uuid,
+        belief: &impl BeliefModel,
+        rng: &mut ChaCha12Rng,
+    ) -> LiarsDiceGame {
+        let mut game = self.clone();
+        // Simulations roll their own dice
+        game.explicit_chance = false;
+        while !game.pending_rolls.is_empty() {
+            game.resolve_roll(Dice::roll(rng));
+        }
+        let hidden: Vec<(Uuid, usize)> = self
+            .players
+            .iter()
+            .filter(|(id, player)| *id != observer && !player.dice.is_empty())
+            .map(|(id, player)| (*id, player.dice.len()))
+            .collect();
+        for (id, count) in &hidden {
+            game.players.get_mut(id).unwrap().dice = (0..*count).map(|_| Dice::roll(rng)).collect();
+        }
+        // The current round's dice are in the history too, and shouldn't give the game away
+        game.sync_round_start();
+        // Beliefs are handed the game with the hidden hands already re-rolled, so they can't peek
+        let view = game.clone();
+        for (id, count) in hidden {
+            game.players.get_mut(&id).unwrap().dice = belief.sample_hand(&view, &id, count, rng);
+        }
+        game.sync_round_start();
+        game.rng = ChaCha12Rng::from_rng(rng);
+        game
+    }
+
+    /// Makes the latest round start in the history match the dice players are actually holding
+    fn sync_round_start(&mut self) {
+        let current = self.player_dices();
+        if let Some(GameEvent::RoundStarted { player_dices }) = self
+            .history
+            .iter_mut()
+            .rev()
+            .find(|event| matches!(event, GameEvent::RoundStarted { .. }))
+        {
+            for (id, dice) in player_dices.iter_mut() {
+                *dice = current[id].clone();
+            }
+        }
+    }
+
+    /// Swaps in dice chosen from outside, such as the dice a record says were rolled
+    pub(crate) fn set_dice(&mut self, player_id: &Uuid, dice: Vec<Dice>) {
+        let player = self.players.get_mut(player_id).unwrap();
+        assert_eq!(player.dice.len(), dice.len(), "Dice count must not change");
+        player.dice = dice;
+        self.sync_round_start();
+    }
+
+    pub(crate) fn set_current_player(&mut self, player_id: Uuid) {
+        assert!(self.current_players.contains(&player_id));
+        self.current_player = Some(player_id);
+    }
+
     pub fn get_state(&mut self) -> LiarsDiceState<'_> {
         match &self.current_player {
             None => LiarsDiceState::Start(GameStartState { game: self }),
+            Some(_) if !self.pending_rolls.is_empty() => {
+                let (player_id, die_index) = self.pending_rolls[0];
+                LiarsDiceState::Chance(ChanceNode {
+                    game: self,
+                    player_id,
+                    die_index,
+                })
+            }
             Some(player_id) => {
                 // There is a player, so are there players with remaining dice?
                 let players_with_dice = self
                     .players
-                    .iter()
-                    .map(|(_, p)| p.dice.len())
+                    .values()
+                    .map(|p| p.dice.len())
                     .filter(|d| *d > 0)
                     .count();
                 match players_with_dice {
                     1 => LiarsDiceState::GameComplete(GameCompleteState {
-                        winner_id: Default::default(),
+                        winner_id: self.winner().unwrap(),
                         game: self,
                     }),
                     0 => unreachable!(),
@@ -191,11 +451,80 @@ impl RollerOrNext {
 pub struct Dice(u8);
 
 impl Dice {
+    /// A die showing `value`, if it is between 1 and 6
+    pub fn new(value: u8) -> Option<Self> {
+        match value {
+            1..=6 => Some(Dice(value)),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
     pub fn roll(rng: &mut ChaCha12Rng) -> Self {
         Dice(rng.random_range(1..=6))
     }
 }
 
+/// Which house rules the game is played with
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum RuleVariant {
+    #[default]
+    Standard,
+    /// Ones count towards every bid, except bids on ones
+    OnesWild,
+    /// The rules of OpenSpiel's `liars_dice`: sixes count towards every bid, a raise may keep the count
+    /// and go up in face instead, and there is no SpotOn
+    OpenSpiel,
+}
+
+impl RuleVariant {
+    /// The face that counts towards bids on any other face, if there is one
+    fn wild_face(&self) -> Option<Dice> {
+        match self {
+            RuleVariant::Standard => None,
+            RuleVariant::OnesWild => Some(Dice(1)),
+            RuleVariant::OpenSpiel => Some(Dice(6)),
+        }
+    }
+
+    /// How many dice count towards a bid on `face`
+    pub fn count_matching(&self, tally: &BTreeMap<Dice, usize>, face: Dice) -> usize {
+        let exact = tally.get(&face).copied().unwrap_or(0);
+        match self.wild_face() {
+            Some(wild) if face != wild => exact + tally.get(&wild).copied().unwrap_or(0),
+            _ => exact,
+        }
+    }
+
+    /// Whether a die showing `die` counts towards a bid on `face`
+    pub fn matches(&self, die: Dice, face: Dice) -> bool {
+        die == face || self.wild_face() == Some(die)
+    }
+
+    /// Chance that a die nobody has seen yet counts towards a bid on `face`
+    pub fn match_chance(&self, face: Dice) -> f64 {
+        match self.wild_face() {
+            Some(wild) if face != wild => 2.0 / 6.0,
+            _ => 1.0 / 6.0,
+        }
+    }
+
+    /// Whether a bid of `bid` outbids `previous`, both as (count, face)
+    pub fn is_raise(&self, previous: (usize, Dice), bid: (usize, Dice)) -> bool {
+        match self {
+            RuleVariant::OpenSpiel => bid > previous,
+            _ => bid.0 > previous.0,
+        }
+    }
+
+    pub fn allows_spot_on(&self) -> bool {
+        *self != RuleVariant::OpenSpiel
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum DiceCall {
     Bullshit,
@@ -203,8 +532,23 @@ pub enum DiceCall {
     Increase { count: usize, value: Dice },
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CallOutcome {
-    player_dices: BTreeMap<Uuid, Vec<Dice>>,
-    tally: BTreeMap<Dice, usize>,
-    correct_call: bool,
+    pub player_dices: BTreeMap<Uuid, Vec<Dice>>,
+    pub tally: BTreeMap<Dice, usize>,
+    pub correct_call: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// Dice were rolled for everyone still in the game
+    RoundStarted {
+        player_dices: BTreeMap<Uuid, Vec<Dice>>,
+    },
+    Called {
+        player_id: Uuid,
+        call: DiceCall,
+    },
+    /// A challenge was resolved and everyone's dice were shown
+    Revealed(CallOutcome),
 }
@@ -0,0 +1,280 @@
+//! An adapter that mirrors OpenSpiel's `State` API, with the action ids, strings and returns of its `liars_dice` game.
+//!
+//! The game itself is a [`LiarsDiceGame`] played with [`RuleVariant::OpenSpiel`] and explicit chance.
+//! Like OpenSpiel, a game is a single round: chance nodes deal every die one at a time, seat 0 bids first,
+//! and the first challenge ends the game.
+use crate::state::LiarsDiceState;
+use crate::{Dice, DiceCall, LiarsDiceGame, RuleVariant};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+pub const CHANCE_PLAYER_ID: i32 = -1;
+pub const TERMINAL_PLAYER_ID: i32 = -4;
+
+/// The OpenSpiel `Game`, describing the sizes of everything
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenSpielGame {
+    pub players: usize,
+    pub starting_dice: u8,
+}
+
+impl OpenSpielGame {
+    pub fn new(players: usize, starting_dice: u8) -> Self {
+        assert!(players > 1 && starting_dice > 0);
+        Self {
+            players,
+            starting_dice,
+        }
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.players
+    }
+
+    fn total_dice(&self) -> usize {
+        self.players * self.starting_dice as usize
+    }
+
+    /// Every bid, then Liar
+    pub fn num_distinct_actions(&self) -> usize {
+        self.total_dice() * 6 + 1
+    }
+
+    fn liar_action(&self) -> usize {
+        self.total_dice() * 6
+    }
+
+    pub fn max_chance_outcomes(&self) -> usize {
+        6
+    }
+
+    /// Every bid in order, then a challenge
+    pub fn max_game_length(&self) -> usize {
+        self.num_distinct_actions()
+    }
+
+    /// Player one-hot, a face one-hot per die, then a bit per action taken
+    pub fn information_state_tensor_size(&self) -> usize {
+        self.players + self.starting_dice as usize * 6 + self.num_distinct_actions()
+    }
+
+    pub fn new_initial_state(&self) -> OpenSpielState {
+        // Ids in seat order, so the engine seats them the same way
+        let seats: Vec<Uuid> = (0..self.players)
+            .map(|i| Uuid::from_u128(i as u128 + 1))
+            .collect();
+        let mut game =
+            LiarsDiceGame::new(ChaCha12Rng::seed_from_u64(0), self.starting_dice, &seats)
+                .with_rules(RuleVariant::OpenSpiel)
+                .with_explicit_chance(true);
+        if let LiarsDiceState::Start(start) = game.get_state() {
+            start.initialise_game();
+        }
+        OpenSpielState {
+            config: *self,
+            game,
+            history: vec![],
+            returns: None,
+        }
+    }
+
+    pub fn action_to_string(&self, player: i32, action: usize) -> String {
+        match player {
+            CHANCE_PLAYER_ID => format!("Roll {}", action + 1),
+            _ if action == self.liar_action() => "Liar".to_string(),
+            _ => format!("{}-{}", action / 6 + 1, action % 6 + 1),
+        }
+    }
+
+    /// Bids are numbered by count and then face, the same order the OpenSpiel rules raise in
+    fn call_action(&self, call: &DiceCall) -> usize {
+        match call {
+            DiceCall::Increase { count, value } => (count - 1) * 6 + (value.0 as usize - 1),
+            DiceCall::Bullshit => self.liar_action(),
+            DiceCall::SpotOn => unreachable!("OpenSpiel rules have no SpotOn"),
+        }
+    }
+
+    fn action_call(&self, action: usize) -> DiceCall {
+        match action == self.liar_action() {
+            true => DiceCall::Bullshit,
+            false => DiceCall::Increase {
+                count: action / 6 + 1,
+                value: Dice((action % 6) as u8 + 1),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenSpielState {
+    config: OpenSpielGame,
+    game: LiarsDiceGame,
+    /// Every die dealt, as chance outcomes, then the bids and the final Liar
+    history: Vec<usize>,
+    returns: Option<Vec<f64>>,
+}
+
+impl OpenSpielState {
+    pub fn current_player(&self) -> i32 {
+        match self.returns {
+            Some(_) => TERMINAL_PLAYER_ID,
+            None if self.history.len() < self.config.total_dice() => CHANCE_PLAYER_ID,
+            None => self.seat_of(&self.game.current_player().unwrap()) as i32,
+        }
+    }
+
+    pub fn is_chance_node(&self) -> bool {
+        self.current_player() == CHANCE_PLAYER_ID
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.returns.is_some()
+    }
+
+    /// Each face is equally likely
+    pub fn chance_outcomes(&self) -> Vec<(usize, f64)> {
+        match self.is_chance_node() {
+            true => (0..6).map(|face| (face, 1.0 / 6.0)).collect(),
+            false => vec![],
+        }
+    }
+
+    pub fn legal_actions(&self) -> Vec<usize> {
+        if self.is_terminal() {
+            return vec![];
+        }
+        if self.is_chance_node() {
+            return (0..6).collect();
+        }
+        let mut actions: Vec<usize> = self
+            .game
+            .legal_calls()
+            .iter()
+            .map(|call| self.config.call_action(call))
+            .collect();
+        actions.sort();
+        actions
+    }
+
+    pub fn apply_action(&mut self, action: usize) -> Result<(), &'static str> {
+        if !self.legal_actions().contains(&action) {
+            return Err("Action is not legal");
+        }
+        match self.is_chance_node() {
+            true => self.deal(Dice(action as u8 + 1)),
+            false => self.play(action),
+        }
+        self.history.push(action);
+        Ok(())
+    }
+
+    fn deal(&mut self, die: Dice) {
+        if let LiarsDiceState::Chance(chance) = self.game.get_state() {
+            chance.resolve(die);
+        }
+        // OpenSpiel always starts with seat 0, whoever the engine picked
+        if self.history.len() + 1 == self.config.total_dice() {
+            self.game.set_current_player(self.game.seating()[0]);
+        }
+    }
+
+    fn play(&mut self, action: usize) {
+        let caller = self.game.current_player().unwrap();
+        let previous_calls = self.game.previous_calls();
+        let outcome = self
+            .game
+            .apply_call(self.config.action_call(action))
+            .expect("Legal actions should apply");
+        let Some(outcome) = outcome else {
+            return;
+        };
+        // Liar is only legal once the seat before has bid
+        let (bidder, _, _) = previous_calls[0];
+        let (winner, loser) = match outcome.correct_call {
+            true => (caller, bidder),
+            false => (bidder, caller),
+        };
+        let mut returns = vec![0.0; self.config.players];
+        returns[self.seat_of(&winner)] = 1.0;
+        returns[self.seat_of(&loser)] = -1.0;
+        self.returns = Some(returns);
+    }
+
+    /// 1 for whoever won the challenge and -1 for whoever lost it, all zero until the game is over
+    pub fn returns(&self) -> Vec<f64> {
+        self.returns
+            .clone()
+            .unwrap_or_else(|| vec![0.0; self.config.players])
+    }
+
+    pub fn history(&self) -> &[usize] {
+        &self.history
+    }
+
+    pub fn action_to_string(&self, player: i32, action: usize) -> String {
+        self.config.action_to_string(player, action)
+    }
+
+    fn seat_of(&self, id: &Uuid) -> usize {
+        self.game
+            .seating()
+            .iter()
+            .position(|seat| seat == id)
+            .unwrap()
+    }
+
+    /// Chance outcomes dealt to `seat` so far, which are faces counted from 0
+    fn dealt(&self, seat: usize) -> &[usize] {
+        let starting_dice = self.config.starting_dice as usize;
+        let dealt = self.history.len().min(self.config.total_dice());
+        let start = (seat * starting_dice).min(dealt);
+        &self.history[start..((seat + 1) * starting_dice).min(dealt)]
+    }
+
+    fn bid_strings(&self) -> String {
+        self.history
+            .iter()
+            .skip(self.config.total_dice())
+            .map(|action| format!(" {}", self.action_to_string(0, *action)))
+            .collect()
+    }
+
+    fn dice_string(&self, seat: usize) -> String {
+        self.dealt(seat)
+            .iter()
+            .map(|face| (face + 1).to_string())
+            .collect()
+    }
+
+    /// The player's own dice, then every bid so far, as in `1245 1-2 2-5 Liar`
+    pub fn information_state_string(&self, player: usize) -> String {
+        format!("{}{}", self.dice_string(player), self.bid_strings())
+    }
+
+    pub fn information_state_tensor(&self, player: usize) -> Vec<f32> {
+        let mut tensor = vec![0.0; self.config.information_state_tensor_size()];
+        tensor[player] = 1.0;
+        let dice_start = self.config.players;
+        for (i, face) in self.dealt(player).iter().enumerate() {
+            tensor[dice_start + i * 6 + face] = 1.0;
+        }
+        let bids_start = dice_start + self.config.starting_dice as usize * 6;
+        for action in self.history.iter().skip(self.config.total_dice()) {
+            tensor[bids_start + action] = 1.0;
+        }
+        tensor
+    }
+}
+
+/// Everyone's dice, then every bid so far
+impl Display for OpenSpielState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let dice: Vec<String> = (0..self.config.players)
+            .map(|seat| self.dice_string(seat))
+            .collect();
+        write!(f, "{}{}", dice.join(" "), self.bid_strings())
+    }
+}
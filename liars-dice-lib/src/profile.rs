@@ -0,0 +1,140 @@
+//! Tendencies of individual players, learnt from the rounds where everyone's dice were revealed.
+use crate::{CallOutcome, DiceCall, GameEvent, RuleVariant};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Running counts for one player. The rates are `None` until there is something to base them on.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    /// Raises made in rounds that were revealed
+    pub bids: u32,
+    /// Raises on a face the player held none of
+    pub bluffs: u32,
+    /// How far bids went past the count expected from the player's own dice, summed over every bid
+    pub aggression_total: f64,
+    /// Turns where there was a bid to challenge
+    pub challenge_opportunities: u32,
+    pub challenges: u32,
+    pub correct_challenges: u32,
+    pub spot_ons: u32,
+    pub correct_spot_ons: u32,
+}
+
+fn rate(count: u32, total: u32) -> Option<f64> {
+    match total {
+        0 => None,
+        total => Some(count as f64 / total as f64),
+    }
+}
+
+impl PlayerProfile {
+    pub fn bluff_rate(&self) -> Option<f64> {
+        rate(self.bluffs, self.bids)
+    }
+
+    /// Average of how many more dice were bid than the player could expect to be there
+    pub fn aggression(&self) -> Option<f64> {
+        match self.bids {
+            0 => None,
+            bids => Some(self.aggression_total / bids as f64),
+        }
+    }
+
+    /// How often the player calls Bullshit when they could
+    pub fn challenge_rate(&self) -> Option<f64> {
+        rate(self.challenges, self.challenge_opportunities)
+    }
+
+    pub fn challenge_accuracy(&self) -> Option<f64> {
+        rate(self.correct_challenges, self.challenges)
+    }
+
+    pub fn spot_on_rate(&self) -> Option<f64> {
+        rate(self.spot_ons, self.challenge_opportunities)
+    }
+
+    pub fn spot_on_accuracy(&self) -> Option<f64> {
+        rate(self.correct_spot_ons, self.spot_ons)
+    }
+}
+
+/// Profiles for everyone seen so far, built up across any number of games
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerProfiles {
+    profiles: BTreeMap<Uuid, PlayerProfile>,
+}
+
+impl PlayerProfiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, player_id: &Uuid) -> Option<&PlayerProfile> {
+        self.profiles.get(player_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Uuid, &PlayerProfile)> {
+        self.profiles.iter()
+    }
+
+    /// Learns from every revealed round in a game's history.
+    /// Call it once per game, as rounds seen before would be counted again.
+    pub fn observe_history(&mut self, rules: RuleVariant, history: &[GameEvent]) {
+        let mut calls = vec![];
+        for event in history {
+            match event {
+                GameEvent::RoundStarted { .. } => calls.clear(),
+                GameEvent::Called { player_id, call } => calls.push((*player_id, *call)),
+                GameEvent::Revealed(outcome) => self.observe_round(rules, &calls, outcome),
+            }
+        }
+    }
+
+    /// Learns from a single round, given its calls in order and how the challenge that ended it went
+    pub fn observe_round(
+        &mut self,
+        rules: RuleVariant,
+        calls: &[(Uuid, DiceCall)],
+        outcome: &CallOutcome,
+    ) {
+        let total_dice: usize = outcome.player_dices.values().map(|dice| dice.len()).sum();
+        for (index, (player_id, call)) in calls.iter().enumerate() {
+            let profile = self.profiles.entry(*player_id).or_default();
+            if index > 0 {
+                profile.challenge_opportunities += 1;
+            }
+            match call {
+                DiceCall::Increase { count, value } => {
+                    let dice = outcome
+                        .player_dices
+                        .get(player_id)
+                        .map_or(&[][..], |dice| dice.as_slice());
+                    let held = dice
+                        .iter()
+                        .filter(|die| rules.matches(**die, *value))
+                        .count();
+                    let unseen = total_dice - dice.len();
+                    let expected = held as f64 + unseen as f64 * rules.match_chance(*value);
+                    profile.bids += 1;
+                    profile.aggression_total += *count as f64 - expected;
+                    if held == 0 {
+                        profile.bluffs += 1;
+                    }
+                }
+                DiceCall::Bullshit => {
+                    profile.challenges += 1;
+                    if outcome.correct_call {
+                        profile.correct_challenges += 1;
+                    }
+                }
+                DiceCall::SpotOn => {
+                    profile.spot_ons += 1;
+                    if outcome.correct_call {
+                        profile.correct_spot_ons += 1;
+                    }
+                }
+            }
+        }
+    }
+}
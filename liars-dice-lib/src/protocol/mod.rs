@@ -0,0 +1,191 @@
+//! A line based text protocol, in the spirit of chess UCI, so bots written in any language can play.
+//!
+//! The referee writes one message per line to the bot:
+//!
+//! ```text
+//! newgame <you> <player>...         a game is starting, with every player in seating order
+//! dice <face>...                    your dice for the round that just started
+//! bid <player> <count>x<face>       someone raised the bid
+//! liar <player>                     someone challenged the bid
+//! spoton <player>                   someone claimed the bid is exactly right
+//! reveal <player>:<face>,<face>...  every hand, shown after a challenge
+//! yourturn                          answer with your call
+//! quit                              the bot should exit
+//! ```
+//!
+//! After `yourturn` the bot answers with a single line: `<count>x<face>`, `liar` or `spoton`.
+mod process;
+
+pub use process::ProcessAgent;
+
+use crate::{Dice, DiceCall};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefereeMessage {
+    NewGame { you: Uuid, players: Vec<Uuid> },
+    Dice(Vec<Dice>),
+    Call { player_id: Uuid, call: DiceCall },
+    Reveal(BTreeMap<Uuid, Vec<Dice>>),
+    YourTurn,
+    Quit,
+}
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    /// The bot took longer than its time limit to answer
+    Timeout,
+    /// The bot closed its output or exited
+    Crashed,
+    Io(std::io::Error),
+    Malformed(String),
+    IllegalCall(&'static str),
+}
+
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolError::Timeout => write!(f, "Bot did not answer in time"),
+            ProtocolError::Crashed => write!(f, "Bot stopped responding"),
+            ProtocolError::Io(e) => write!(f, "Could not talk to bot: {e}"),
+            ProtocolError::Malformed(line) => write!(f, "Could not understand {line:?}"),
+            ProtocolError::IllegalCall(reason) => write!(f, "Illegal call: {reason}"),
+        }
+    }
+}
+
+impl ProtocolError {
+    /// A short reason for forfeiting the game, without the details
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ProtocolError::Timeout => "Bot did not answer in time",
+            ProtocolError::Crashed => "Bot stopped responding",
+            ProtocolError::Io(_) => "Could not talk to bot",
+            ProtocolError::Malformed(_) => "Bot did not answer with a call",
+            ProtocolError::IllegalCall(reason) => reason,
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<std::io::Error> for ProtocolError {
+    fn from(e: std::io::Error) -> Self {
+        ProtocolError::Io(e)
+    }
+}
+
+/// The bot's side of a turn, `4x5`, `liar` or `spoton`
+pub fn format_call(call: &DiceCall) -> String {
+    match call {
+        DiceCall::Bullshit => "liar".to_string(),
+        DiceCall::SpotOn => "spoton".to_string(),
+        DiceCall::Increase { count, value } => format!("{count}x{}", value.0),
+    }
+}
+
+pub fn parse_call(line: &str) -> Result<DiceCall, ProtocolError> {
+    let malformed = || ProtocolError::Malformed(line.to_string());
+    match line.trim().to_ascii_lowercase().as_str() {
+        "liar" => Ok(DiceCall::Bullshit),
+        "spoton" => Ok(DiceCall::SpotOn),
+        bid => {
+            let (count, face) = bid.split_once('x').ok_or_else(malformed)?;
+            Ok(DiceCall::Increase {
+                count: count.parse().map_err(|_| malformed())?,
+                value: parse_face(face).ok_or_else(malformed)?,
+            })
+        }
+    }
+}
+
+fn parse_face(face: &str) -> Option<Dice> {
+    match face.parse() {
+        Ok(value @ 1..=6) => Some(Dice(value)),
+        _ => None,
+    }
+}
+
+fn format_faces(dice: &[Dice], separator: &str) -> String {
+    dice.iter()
+        .map(|d| d.0.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+impl Display for RefereeMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefereeMessage::NewGame { you, players } => {
+                write!(f, "newgame {you}")?;
+                for p in players {
+                    write!(f, " {p}")?;
+                }
+                Ok(())
+            }
+            RefereeMessage::Dice(dice) => write!(f, "dice {}", format_faces(dice, " ")),
+            RefereeMessage::Call { player_id, call } => match call {
+                DiceCall::Increase { .. } => write!(f, "bid {player_id} {}", format_call(call)),
+                _ => write!(f, "{} {player_id}", format_call(call)),
+            },
+            RefereeMessage::Reveal(player_dices) => {
+                write!(f, "reveal")?;
+                for (id, dice) in player_dices {
+                    write!(f, " {id}:{}", format_faces(dice, ","))?;
+                }
+                Ok(())
+            }
+            RefereeMessage::YourTurn => write!(f, "yourturn"),
+            RefereeMessage::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for RefereeMessage {
+    type Err = ProtocolError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let malformed = || ProtocolError::Malformed(line.to_string());
+        let uuid = |s: &str| Uuid::parse_str(s).map_err(|_| malformed());
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["newgame", you, players @ ..] => Ok(RefereeMessage::NewGame {
+                you: uuid(you)?,
+                players: players.iter().map(|p| uuid(p)).collect::<Result<_, _>>()?,
+            }),
+            ["dice", faces @ ..] => Ok(RefereeMessage::Dice(
+                faces
+                    .iter()
+                    .map(|f| parse_face(f).ok_or_else(malformed))
+                    .collect::<Result<_, _>>()?,
+            )),
+            ["bid", player_id, bid] => Ok(RefereeMessage::Call {
+                player_id: uuid(player_id)?,
+                call: parse_call(bid)?,
+            }),
+            [call @ ("liar" | "spoton"), player_id] => Ok(RefereeMessage::Call {
+                player_id: uuid(player_id)?,
+                call: parse_call(call)?,
+            }),
+            ["reveal", hands @ ..] => {
+                let mut player_dices = BTreeMap::new();
+                for hand in hands {
+                    let (id, faces) = hand.split_once(':').ok_or_else(malformed)?;
+                    let dice = faces
+                        .split(',')
+                        .filter(|f| !f.is_empty())
+                        .map(|f| parse_face(f).ok_or_else(malformed))
+                        .collect::<Result<_, _>>()?;
+                    player_dices.insert(uuid(id)?, dice);
+                }
+                Ok(RefereeMessage::Reveal(player_dices))
+            }
+            ["yourturn"] => Ok(RefereeMessage::YourTurn),
+            ["quit"] => Ok(RefereeMessage::Quit),
+            _ => Err(malformed()),
+        }
+    }
+}
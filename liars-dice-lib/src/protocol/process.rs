@@ -0,0 +1,139 @@
+use crate::agent::Agent;
+use crate::protocol::{ProtocolError, RefereeMessage, parse_call};
+use crate::{DiceCall, GameEvent, LiarsDiceGame};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Plays through a child process that speaks the text protocol on stdin and stdout.
+///
+/// Once the bot times out, crashes or makes an illegal call, the error is kept and reported
+/// as the agent's failure, so the game is forfeited rather than played on with placeholder calls.
+/// The bot is never asked again after that.
+pub struct ProcessAgent {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    timeout: Duration,
+    /// The first event of the game the bot is playing, to notice when a new game starts
+    game_start: Option<GameEvent>,
+    /// How much of the game history the bot has been told about
+    sent: usize,
+    error: Option<ProtocolError>,
+}
+
+impl ProcessAgent {
+    pub fn spawn(mut command: Command, timeout: Duration) -> Result<Self, ProtocolError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin should be piped");
+        let stdout = child.stdout.take().expect("stdout should be piped");
+        let (tx, lines) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+            timeout,
+            game_start: None,
+            sent: 0,
+            error: None,
+        })
+    }
+
+    /// Why the bot stopped being asked for its calls, if it has
+    pub fn error(&self) -> Option<&ProtocolError> {
+        self.error.as_ref()
+    }
+
+    fn send(&mut self, message: RefereeMessage) -> Result<(), ProtocolError> {
+        writeln!(self.stdin, "{message}")?;
+        Ok(())
+    }
+
+    /// Tells the bot everything that happened since it was last asked
+    fn catch_up(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> Result<(), ProtocolError> {
+        let history = game.history();
+        if self.game_start.as_ref() != history.first() || self.sent > history.len() {
+            self.game_start = history.first().cloned();
+            self.sent = 0;
+            self.send(RefereeMessage::NewGame {
+                you: player_id,
+                players: game.seating().to_vec(),
+            })?;
+        }
+        for event in &history[self.sent..] {
+            let message = match event {
+                GameEvent::RoundStarted { player_dices } => {
+                    let dice = player_dices.get(&player_id).cloned().unwrap_or_default();
+                    RefereeMessage::Dice(dice)
+                }
+                GameEvent::Called { player_id, call } => RefereeMessage::Call {
+                    player_id: *player_id,
+                    call: *call,
+                },
+                GameEvent::Revealed(outcome) => {
+                    RefereeMessage::Reveal(outcome.player_dices.clone())
+                }
+            };
+            self.send(message)?;
+        }
+        self.sent = history.len();
+        Ok(())
+    }
+
+    fn ask(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> Result<DiceCall, ProtocolError> {
+        self.catch_up(game, player_id)?;
+        // Anything the bot said out of turn isn't an answer to this question
+        while self.lines.try_recv().is_ok() {}
+        self.send(RefereeMessage::YourTurn)?;
+        self.stdin.flush()?;
+        let line = self.lines.recv_timeout(self.timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => ProtocolError::Timeout,
+            RecvTimeoutError::Disconnected => ProtocolError::Crashed,
+        })?;
+        let call = parse_call(&line)?;
+        game.clone()
+            .apply_call(call)
+            .map_err(ProtocolError::IllegalCall)?;
+        Ok(call)
+    }
+}
+
+impl Agent for ProcessAgent {
+    fn choose_call(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> DiceCall {
+        if self.error.is_none() {
+            match self.ask(game, player_id) {
+                Ok(call) => return call,
+                Err(e) => self.error = Some(e),
+            }
+        }
+        // Never played, since the failure forfeits the game
+        game.legal_calls()[0]
+    }
+
+    fn failure(&self) -> Option<&'static str> {
+        self.error.as_ref().map(ProtocolError::reason)
+    }
+}
+
+impl Drop for ProcessAgent {
+    fn drop(&mut self) {
+        let _ = self.send(RefereeMessage::Quit);
+        let _ = self.stdin.flush();
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,316 @@
+//! Python bindings, built with `maturin develop` from this crate's directory.
+//!
+//! ```python
+//! import liars_dice
+//! env = liars_dice.LiarsDiceEnv(players=2, starting_dice=5, seed=0)
+//! obs, mask = env.reset()
+//! obs, mask, rewards, done = env.step(mask.nonzero()[0][0])
+//! ```
+use crate::LiarsDiceGame;
+use crate::encoding::{ObservationEncoder, decode_call};
+use crate::openspiel::{OpenSpielGame, OpenSpielState};
+use crate::protocol::format_call;
+use crate::state::LiarsDiceState;
+use numpy::PyArray1;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use uuid::Uuid;
+
+type Observation<'py> = (Bound<'py, PyArray1<f32>>, Bound<'py, PyArray1<f32>>);
+type Step<'py> = (
+    Bound<'py, PyArray1<f32>>,
+    Bound<'py, PyArray1<f32>>,
+    Bound<'py, PyArray1<f32>>,
+    bool,
+);
+/// A step before it is turned into arrays
+type RawStep = (Vec<f32>, Vec<f32>, Vec<f32>, bool);
+
+/// A step/reset environment over a single game.
+/// Seats are numbered from 0, and every step is taken by whoever's turn it is.
+#[pyclass(name = "LiarsDiceEnv", module = "liars_dice")]
+pub struct PyLiarsDiceEnv {
+    game: LiarsDiceGame,
+    encoder: ObservationEncoder,
+    seats: Vec<Uuid>,
+    seed: u64,
+}
+
+impl PyLiarsDiceEnv {
+    fn seat_of(&self, player_id: &Uuid) -> usize {
+        self.seats.iter().position(|id| id == player_id).unwrap()
+    }
+
+    fn seat_id(&self, seat: usize) -> PyResult<Uuid> {
+        self.seats
+            .get(seat)
+            .copied()
+            .ok_or_else(|| PyValueError::new_err(format!("No seat {seat}")))
+    }
+
+    /// What the player to act sees, or the winner once the game is over, with the legal action mask
+    fn current_observation(&self) -> (Vec<f32>, Vec<f32>) {
+        let observer = self
+            .game
+            .current_player()
+            .filter(|_| !self.game.is_complete())
+            .or(self.game.winner())
+            .unwrap();
+        (
+            self.encoder.encode(&self.game, &observer),
+            self.encoder.legal_action_mask(&self.game),
+        )
+    }
+
+    /// [`Self::reset`] without the arrays, so it can be used without an interpreter
+    pub(crate) fn start_game(&mut self, seed: Option<u64>) -> (Vec<f32>, Vec<f32>) {
+        self.seed = seed.unwrap_or(self.seed + 1);
+        self.game = LiarsDiceGame::new(
+            ChaCha12Rng::seed_from_u64(self.seed),
+            self.encoder.starting_dice,
+            &self.seats,
+        );
+        if let LiarsDiceState::Start(start) = self.game.get_state() {
+            start.initialise_game();
+        }
+        self.current_observation()
+    }
+
+    /// [`Self::step`] without the arrays, so it can be used without an interpreter
+    pub(crate) fn play_action(&mut self, action: usize) -> Result<RawStep, String> {
+        if action >= self.encoder.action_count() {
+            return Err(format!("No action {action}"));
+        }
+        self.game.apply_call(decode_call(action))?;
+        let mut rewards = vec![0.0; self.seats.len()];
+        let winner = self.game.winner();
+        if let Some(winner) = winner {
+            rewards.fill(-1.0);
+            rewards[self.seat_of(&winner)] = 1.0;
+        }
+        let (observation, mask) = self.current_observation();
+        Ok((observation, mask, rewards, winner.is_some()))
+    }
+}
+
+#[pymethods]
+impl PyLiarsDiceEnv {
+    #[new]
+    #[pyo3(signature = (players=2, starting_dice=5, seed=0))]
+    pub(crate) fn new(players: usize, starting_dice: u8, seed: u64) -> PyResult<Self> {
+        if players < 2 || starting_dice == 0 {
+            return Err(PyValueError::new_err(
+                "Need at least two players with at least one die each",
+            ));
+        }
+        let seats: Vec<Uuid> = (0..players)
+            .map(|i| Uuid::from_u128(i as u128 + 1))
+            .collect();
+        Ok(Self {
+            game: LiarsDiceGame::new(ChaCha12Rng::seed_from_u64(seed), starting_dice, &seats),
+            encoder: ObservationEncoder::new(players, starting_dice),
+            seats,
+            seed,
+        })
+    }
+
+    /// Starts a new game, returning the first player's observation and legal action mask.
+    /// Without a seed, the next game in the environment's sequence is played.
+    #[pyo3(signature = (seed=None))]
+    fn reset<'py>(&mut self, py: Python<'py>, seed: Option<u64>) -> Observation<'py> {
+        let (observation, mask) = self.start_game(seed);
+        (
+            PyArray1::from_vec(py, observation),
+            PyArray1::from_vec(py, mask),
+        )
+    }
+
+    /// Plays `action` for the current player.
+    /// Returns the next observation and mask, a reward per seat, and whether the game is over.
+    /// Rewards are 1 for the winner and -1 for everyone else at the end, and 0 until then.
+    fn step<'py>(&mut self, py: Python<'py>, action: usize) -> PyResult<Step<'py>> {
+        let (observation, mask, rewards, done) =
+            self.play_action(action).map_err(PyValueError::new_err)?;
+        Ok((
+            PyArray1::from_vec(py, observation),
+            PyArray1::from_vec(py, mask),
+            PyArray1::from_vec(py, rewards),
+            done,
+        ))
+    }
+
+    /// Seat whose turn it is, if the game is still going
+    #[getter]
+    fn current_player(&self) -> Option<usize> {
+        match self.game.is_complete() {
+            true => None,
+            false => self.game.current_player().map(|id| self.seat_of(&id)),
+        }
+    }
+
+    #[getter]
+    fn winner(&self) -> Option<usize> {
+        self.game.winner().map(|id| self.seat_of(&id))
+    }
+
+    #[getter]
+    fn observation_size(&self) -> usize {
+        self.encoder.size()
+    }
+
+    #[getter]
+    fn action_size(&self) -> usize {
+        self.encoder.action_count()
+    }
+
+    fn observation<'py>(
+        &self,
+        py: Python<'py>,
+        seat: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let id = self.seat_id(seat)?;
+        Ok(PyArray1::from_vec(py, self.encoder.encode(&self.game, &id)))
+    }
+
+    fn legal_action_mask<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
+        PyArray1::from_vec(py, self.encoder.legal_action_mask(&self.game))
+    }
+
+    fn legal_actions(&self) -> Vec<usize> {
+        self.game
+            .legal_calls()
+            .iter()
+            .map(crate::encoding::encode_call)
+            .collect()
+    }
+
+    /// Face values held by a seat
+    fn dice(&self, seat: usize) -> PyResult<Vec<u8>> {
+        let id = self.seat_id(seat)?;
+        Ok(self.game.player_dices()[&id].iter().map(|d| d.0).collect())
+    }
+}
+
+/// Mirrors `pyspiel.Game` for the single round OpenSpiel flavour of the game
+#[pyclass(name = "OpenSpielGame", module = "liars_dice")]
+pub struct PyOpenSpielGame(OpenSpielGame);
+
+#[pymethods]
+impl PyOpenSpielGame {
+    #[new]
+    #[pyo3(signature = (players=2, starting_dice=5))]
+    fn new(players: usize, starting_dice: u8) -> PyResult<Self> {
+        if players < 2 || starting_dice == 0 {
+            return Err(PyValueError::new_err(
+                "Need at least two players with at least one die each",
+            ));
+        }
+        Ok(Self(OpenSpielGame::new(players, starting_dice)))
+    }
+
+    fn new_initial_state(&self) -> PyOpenSpielState {
+        PyOpenSpielState(self.0.new_initial_state())
+    }
+
+    fn num_players(&self) -> usize {
+        self.0.num_players()
+    }
+
+    fn num_distinct_actions(&self) -> usize {
+        self.0.num_distinct_actions()
+    }
+
+    fn max_chance_outcomes(&self) -> usize {
+        self.0.max_chance_outcomes()
+    }
+
+    fn max_game_length(&self) -> usize {
+        self.0.max_game_length()
+    }
+
+    fn information_state_tensor_size(&self) -> usize {
+        self.0.information_state_tensor_size()
+    }
+}
+
+/// Mirrors `pyspiel.State`
+#[pyclass(name = "OpenSpielState", module = "liars_dice")]
+#[derive(Clone)]
+pub struct PyOpenSpielState(OpenSpielState);
+
+#[pymethods]
+impl PyOpenSpielState {
+    fn current_player(&self) -> i32 {
+        self.0.current_player()
+    }
+
+    fn is_chance_node(&self) -> bool {
+        self.0.is_chance_node()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.0.is_terminal()
+    }
+
+    fn chance_outcomes(&self) -> Vec<(usize, f64)> {
+        self.0.chance_outcomes()
+    }
+
+    fn legal_actions(&self) -> Vec<usize> {
+        self.0.legal_actions()
+    }
+
+    fn apply_action(&mut self, action: usize) -> PyResult<()> {
+        self.0.apply_action(action).map_err(PyValueError::new_err)
+    }
+
+    fn returns(&self) -> Vec<f64> {
+        self.0.returns()
+    }
+
+    fn history(&self) -> Vec<usize> {
+        self.0.history().to_vec()
+    }
+
+    fn action_to_string(&self, player: i32, action: usize) -> String {
+        self.0.action_to_string(player, action)
+    }
+
+    fn information_state_string(&self, player: usize) -> String {
+        self.0.information_state_string(player)
+    }
+
+    fn information_state_tensor<'py>(
+        &self,
+        py: Python<'py>,
+        player: usize,
+    ) -> Bound<'py, PyArray1<f32>> {
+        PyArray1::from_vec(py, self.0.information_state_tensor(player))
+    }
+
+    fn clone(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// `4x5`, `liar` or `spoton`, as written in the bot protocol
+#[pyfunction]
+fn action_to_string(action: usize) -> String {
+    format_call(&decode_call(action))
+}
+
+#[pymodule]
+#[pyo3(name = "liars_dice")]
+fn liars_dice(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyLiarsDiceEnv>()?;
+    m.add_class::<PyOpenSpielGame>()?;
+    m.add_class::<PyOpenSpielState>()?;
+    m.add_function(wrap_pyfunction!(action_to_string, m)?)?;
+    Ok(())
+}
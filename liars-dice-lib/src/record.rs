@@ -0,0 +1,505 @@
+//! A text notation for whole games, in the spirit of chess PGN, for sharing and annotating games.
+//!
+//! ```text
+//! [Rules "Standard"]
+//! [Seed "42"]
+//! [StartingDice "2"]
+//! [Players "A B C"]
+//! [Winner "B"]
+//!
+//! ; Lines starting with a semicolon are ignored
+//! R1: A 2x4, B 3x4 {bold}, C 4x6, A LIAR -> [A:24 B:13 C:66] C -1
+//! R2: A 1x2, B 2x2, C SPOTON -> [A:22 B:51 C:3] A -1 B -1
+//! R3: C 1x5, A LIAR -> [A:4 B:2 C:5] A -1
+//! R4: B 1x3, C LIAR -> [B:3 C:1] C -1
+//! ```
+//!
+//! Each round is one line: the calls in order, then everyone's dice and who lost a die.
+//! A comment in braces can follow any call. The last round may stop before its challenge.
+//! Players are seated in the order they are listed, and get the ids 1, 2, 3... when replayed.
+use crate::protocol::{format_call, parse_call};
+use crate::state::LiarsDiceState;
+use crate::{Dice, DiceCall, GameEvent, LiarsDiceGame, RuleVariant};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub rules: RuleVariant,
+    /// Seed the game was played with, if it is known
+    pub seed: Option<u64>,
+    pub starting_dice: u8,
+    /// Player names in seating order
+    pub players: Vec<String>,
+    pub winner: Option<String>,
+    /// Any other header tags, such as `Event` or `Annotator`, in the order they appeared
+    pub tags: Vec<(String, String)>,
+    pub rounds: Vec<RoundRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundRecord {
+    pub calls: Vec<CallRecord>,
+    /// Everyone's dice, in seating order, once the round has been challenged
+    pub reveal: Option<Vec<(String, Vec<Dice>)>>,
+    /// Players who lost a die to the challenge
+    pub losers: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallRecord {
+    pub player: String,
+    pub call: DiceCall,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError {
+    /// Line of the record the error is on, counting from 1
+    pub line: usize,
+    pub reason: &'static str,
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+/// `A` to `Z`, then `P27` onwards
+fn seat_name(seat: usize) -> String {
+    match seat {
+        0..26 => ((b'A' + seat as u8) as char).to_string(),
+        _ => format!("P{}", seat + 1),
+    }
+}
+
+fn format_rules(rules: RuleVariant) -> &'static str {
+    match rules {
+        RuleVariant::Standard => "Standard",
+        RuleVariant::OnesWild => "OnesWild",
+        RuleVariant::OpenSpiel => "OpenSpiel",
+    }
+}
+
+fn parse_rules(rules: &str) -> Option<RuleVariant> {
+    match rules {
+        "Standard" => Some(RuleVariant::Standard),
+        "OnesWild" => Some(RuleVariant::OnesWild),
+        "OpenSpiel" => Some(RuleVariant::OpenSpiel),
+        _ => None,
+    }
+}
+
+fn format_faces(dice: &[Dice]) -> String {
+    dice.iter().map(|d| d.0.to_string()).collect()
+}
+
+impl GameRecord {
+    /// Records a game from its history, naming the seats `A`, `B`, `C`...
+    /// The seed isn't known to the game, so set it afterwards if it should be in the record.
+    pub fn from_game(game: &LiarsDiceGame) -> Self {
+        let seats = game.seating();
+        let name = |id: &Uuid| seat_name(seats.iter().position(|seat| seat == id).unwrap());
+        let hands = |player_dices: &BTreeMap<Uuid, Vec<Dice>>| -> Vec<(String, Vec<Dice>)> {
+            seats
+                .iter()
+                .filter(|id| player_dices.get(id).is_some_and(|dice| !dice.is_empty()))
+                .map(|id| (name(id), player_dices[id].clone()))
+                .collect()
+        };
+        let mut rounds: Vec<RoundRecord> = vec![];
+        let history = game.history();
+        for (index, event) in history.iter().enumerate() {
+            match event {
+                GameEvent::RoundStarted { .. } => rounds.push(RoundRecord {
+                    calls: vec![],
+                    reveal: None,
+                    losers: vec![],
+                }),
+                GameEvent::Called { player_id, call } => {
+                    rounds.last_mut().unwrap().calls.push(CallRecord {
+                        player: name(player_id),
+                        call: *call,
+                        comment: None,
+                    })
+                }
+                GameEvent::Revealed(outcome) => {
+                    // Whoever has fewer dice at the start of the next round lost one
+                    let after = history[index..]
+                        .iter()
+                        .find_map(|event| match event {
+                            GameEvent::RoundStarted { player_dices } => Some(player_dices.clone()),
+                            _ => None,
+                        })
+                        .unwrap_or_else(|| game.player_dices());
+                    let round = rounds.last_mut().unwrap();
+                    round.losers = seats
+                        .iter()
+                        .filter(|id| {
+                            let before = outcome.player_dices.get(id).map_or(0, |d| d.len());
+                            let after = after.get(id).map_or(0, |d| d.len());
+                            after < before
+                        })
+                        .map(name)
+                        .collect();
+                    round.reveal = Some(hands(&outcome.player_dices));
+                }
+            }
+        }
+        // A round that was rolled but never bid on isn't worth recording
+        if rounds.last().is_some_and(|round| round.calls.is_empty()) {
+            rounds.pop();
+        }
+        Self {
+            rules: game.rules(),
+            seed: None,
+            starting_dice: game.starting_dice(),
+            players: (0..seats.len()).map(seat_name).collect(),
+            winner: game.winner().map(|id| name(&id)),
+            tags: vec![],
+            rounds,
+        }
+    }
+
+    fn seat(&self, name: &str) -> Result<usize, &'static str> {
+        self.players
+            .iter()
+            .position(|player| player == name)
+            .ok_or("Unknown player")
+    }
+
+    /// Plays the record back through the engine, checking every call is legal and every
+    /// challenge knocks out the dice the record says it did.
+    /// Dice come from the reveals, so the seed only matters for a round that was never challenged.
+    pub fn replay(&self) -> Result<LiarsDiceGame, &'static str> {
+        let seats: Vec<Uuid> = (0..self.players.len())
+            .map(|i| Uuid::from_u128(i as u128 + 1))
+            .collect();
+        if seats.len() < 2 || self.starting_dice == 0 {
+            return Err("Need at least two players with at least one die each");
+        }
+        let rng = ChaCha12Rng::seed_from_u64(self.seed.unwrap_or(0));
+        let mut game = LiarsDiceGame::new(rng, self.starting_dice, &seats).with_rules(self.rules);
+        if let LiarsDiceState::Start(start) = game.get_state() {
+            start.initialise_game();
+        }
+        for (index, round) in self.rounds.iter().enumerate() {
+            if game.is_complete() {
+                return Err("Round played after the game was over");
+            }
+            let first = round.calls.first().ok_or("Every round needs a call")?;
+            let first_id = seats[self.seat(&first.player)?];
+            match index {
+                // Who starts the game is down to the rng, so take the record's word for it
+                0 => game.set_current_player(first_id),
+                _ if game.current_player() != Some(first_id) => {
+                    return Err("Round started with the wrong player");
+                }
+                _ => (),
+            }
+            if let Some(reveal) = &round.reveal {
+                if reveal.len() != game.current_players().len() {
+                    return Err("Reveal must show everyone still in the game");
+                }
+                for (name, dice) in reveal {
+                    let id = seats[self.seat(name)?];
+                    if game.players[&id].dice.len() != dice.len() {
+                        return Err("Revealed dice don't match the dice in play");
+                    }
+                    game.set_dice(&id, dice.clone());
+                }
+            }
+            let before = game.player_dices();
+            let mut challenged = false;
+            for call in &round.calls {
+                if challenged {
+                    return Err("Round continued after a challenge");
+                }
+                if game.current_player() != Some(seats[self.seat(&call.player)?]) {
+                    return Err("Call made out of turn");
+                }
+                challenged = game.apply_call(call.call)?.is_some();
+            }
+            match (challenged, round.reveal.is_some()) {
+                (true, false) => return Err("Challenged round must have a reveal"),
+                (false, true) => return Err("Revealed round must end in a challenge"),
+                (false, false) if index + 1 < self.rounds.len() => {
+                    return Err("Only the last round can be left unfinished");
+                }
+                _ => (),
+            }
+            let after = game.player_dices();
+            let losers: Vec<&String> = seats
+                .iter()
+                .zip(&self.players)
+                .filter(|(id, _)| after[id].len() < before[id].len())
+                .map(|(_, name)| name)
+                .collect();
+            let mut recorded: Vec<&String> = round.losers.iter().collect();
+            recorded.sort_by_key(|name| self.seat(name).unwrap_or(usize::MAX));
+            if losers != recorded {
+                return Err("Losers don't match the replay");
+            }
+        }
+        let winner = game
+            .winner()
+            .map(|id| self.players[seats.iter().position(|seat| *seat == id).unwrap()].clone());
+        if self.winner.is_some() && self.winner != winner {
+            return Err("Winner doesn't match the replay");
+        }
+        Ok(game)
+    }
+}
+
+impl Display for CallRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let call = match self.call {
+            DiceCall::Increase { .. } => format_call(&self.call),
+            _ => format_call(&self.call).to_uppercase(),
+        };
+        write!(f, "{} {call}", self.player)?;
+        if let Some(comment) = &self.comment {
+            write!(f, " {{{comment}}}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[Rules \"{}\"]", format_rules(self.rules))?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "[Seed \"{seed}\"]")?;
+        }
+        writeln!(f, "[StartingDice \"{}\"]", self.starting_dice)?;
+        writeln!(f, "[Players \"{}\"]", self.players.join(" "))?;
+        if let Some(winner) = &self.winner {
+            writeln!(f, "[Winner \"{winner}\"]")?;
+        }
+        for (key, value) in &self.tags {
+            writeln!(f, "[{key} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+        for (index, round) in self.rounds.iter().enumerate() {
+            let calls: Vec<String> = round.calls.iter().map(|call| call.to_string()).collect();
+            write!(f, "R{}: {}", index + 1, calls.join(", "))?;
+            if let Some(reveal) = &round.reveal {
+                let hands: Vec<String> = reveal
+                    .iter()
+                    .map(|(name, dice)| format!("{name}:{}", format_faces(dice)))
+                    .collect();
+                write!(f, " -> [{}]", hands.join(" "))?;
+                for loser in &round.losers {
+                    write!(f, " {loser} -1")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Word(&'a str),
+    Comment(&'a str),
+    Arrow,
+    Open,
+    Close,
+}
+
+/// Splits a round into words, comments and punctuation, treating commas as spaces
+fn tokenize(line: &str) -> Result<Vec<Token<'_>>, &'static str> {
+    let mut tokens = vec![];
+    let mut rest = line;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        let Some(c) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+        let end = match c {
+            '{' => {
+                let close = rest.find('}').ok_or("Comment is never closed")?;
+                tokens.push(Token::Comment(rest[1..close].trim()));
+                close + 1
+            }
+            '[' => {
+                tokens.push(Token::Open);
+                1
+            }
+            ']' => {
+                tokens.push(Token::Close);
+                1
+            }
+            _ if rest.starts_with("->") => {
+                tokens.push(Token::Arrow);
+                2
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || ",{[]".contains(c))
+                    .unwrap_or(rest.len());
+                tokens.push(Token::Word(&rest[..end]));
+                end
+            }
+        };
+        rest = &rest[end..];
+    }
+}
+
+/// Reads a `[Key "Value"]` tag
+fn parse_tag(line: &str) -> Option<(&str, &str)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (key, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((key, value))
+}
+
+impl GameRecord {
+    fn parse_round(&self, line: &str) -> Result<RoundRecord, &'static str> {
+        let (label, rest) = line
+            .split_once(':')
+            .ok_or("Round must start with its number")?;
+        if label.strip_prefix('R') != Some(&(self.rounds.len() + 1).to_string()) {
+            return Err("Rounds must be numbered in order");
+        }
+        let player = |name: &str| self.seat(name).map(|_| name.to_string());
+        let mut round = RoundRecord {
+            calls: vec![],
+            reveal: None,
+            losers: vec![],
+        };
+        let mut tokens = tokenize(rest)?.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                Token::Word(name) => {
+                    let Some(Token::Word(call)) = tokens.next() else {
+                        return Err("Player must be followed by a call");
+                    };
+                    let comment = match tokens.peek() {
+                        Some(Token::Comment(comment)) => Some(comment.to_string()),
+                        _ => None,
+                    };
+                    if comment.is_some() {
+                        tokens.next();
+                    }
+                    round.calls.push(CallRecord {
+                        player: player(name)?,
+                        call: parse_call(call).map_err(|_| "Could not read call")?,
+                        comment,
+                    });
+                }
+                Token::Arrow => break,
+                _ => return Err("Expected a call"),
+            }
+        }
+        if tokens.peek().is_none() {
+            return Ok(round);
+        }
+        if tokens.next() != Some(Token::Open) {
+            return Err("Reveal must be in brackets");
+        }
+        let mut reveal = vec![];
+        loop {
+            match tokens.next() {
+                Some(Token::Word(hand)) => {
+                    let (name, faces) = hand.split_once(':').ok_or("Hand must be player:faces")?;
+                    let dice = faces
+                        .chars()
+                        .map(|c| c.to_digit(10).and_then(|value| Dice::new(value as u8)))
+                        .collect::<Option<Vec<Dice>>>()
+                        .ok_or("Dice faces must be between 1 and 6")?;
+                    reveal.push((player(name)?, dice));
+                }
+                Some(Token::Close) => break,
+                _ => return Err("Reveal is never closed"),
+            }
+        }
+        round.reveal = Some(reveal);
+        while let Some(token) = tokens.next() {
+            match (token, tokens.next()) {
+                (Token::Word(name), Some(Token::Word("-1"))) => round.losers.push(player(name)?),
+                _ => return Err("Losers must be written as player -1"),
+            }
+        }
+        Ok(round)
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut record = GameRecord {
+            rules: RuleVariant::Standard,
+            seed: None,
+            starting_dice: 0,
+            players: vec![],
+            winner: None,
+            tags: vec![],
+            rounds: vec![],
+        };
+        let mut winner_line = 0;
+        for (index, line) in text.lines().enumerate() {
+            let error = |reason| RecordError {
+                line: index + 1,
+                reason,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            if line.starts_with('[') {
+                if !record.rounds.is_empty() {
+                    return Err(error("Tags must come before the rounds"));
+                }
+                let (key, value) = parse_tag(line).ok_or(error("Tag must be [Key \"Value\"]"))?;
+                match key {
+                    "Rules" => record.rules = parse_rules(value).ok_or(error("Unknown rules"))?,
+                    "Seed" => record.seed = Some(value.parse().map_err(|_| error("Bad seed"))?),
+                    "StartingDice" => {
+                        record.starting_dice =
+                            value.parse().map_err(|_| error("Bad starting dice"))?
+                    }
+                    "Players" => {
+                        record.players = value.split_whitespace().map(str::to_string).collect();
+                        let mut names = record.players.clone();
+                        names.sort();
+                        names.dedup();
+                        if names.len() != record.players.len() {
+                            return Err(error("Player names must be unique"));
+                        }
+                        if record.players.iter().any(|name| name.contains(':')) {
+                            return Err(error("Player names can't contain a colon"));
+                        }
+                    }
+                    "Winner" => {
+                        record.winner = Some(value.to_string());
+                        winner_line = index + 1;
+                    }
+                    _ => record.tags.push((key.to_string(), value.to_string())),
+                }
+                continue;
+            }
+            if record.players.is_empty() || record.starting_dice == 0 {
+                return Err(error("Players and StartingDice tags must come first"));
+            }
+            let round = record.parse_round(line).map_err(error)?;
+            record.rounds.push(round);
+        }
+        if let Some(winner) = &record.winner {
+            record.seat(winner).map_err(|reason| RecordError {
+                line: winner_line,
+                reason,
+            })?;
+        }
+        Ok(record)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{Dice, DiceCall, LiarsDiceGame, LiarsDicePlayer, RuleVariant};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Sets up a game part way through a round, with dice and bids chosen rather than rolled.
+///
+/// ```
+/// # use liars_dice_lib::scenario::ScenarioBuilder;
+/// # use uuid::Uuid;
+/// let (a, b) = (Uuid::from_u128(1), Uuid::from_u128(2));
+/// let game = ScenarioBuilder::new()
+///     .player(a, &[2, 2, 5])
+///     .player(b, &[3, 6])
+///     .current_player(a)
+///     .bids(&[(2, 3), (3, 2)])
+///     .build()
+///     .unwrap();
+/// assert_eq!(game.current_player(), Some(a));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioBuilder {
+    /// Players in seating order, with their dice
+    players: Vec<(Uuid, Vec<u8>)>,
+    current_player: Option<Uuid>,
+    bids: Vec<(usize, u8)>,
+    starting_dice: Option<u8>,
+    rules: RuleVariant,
+    seed: u64,
+}
+
+impl ScenarioBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seats a player with the given faces. Players are seated in the order they are added,
+    /// and a player with no dice has already been knocked out.
+    pub fn player(mut self, id: Uuid, faces: &[u8]) -> Self {
+        self.players.push((id, faces.to_vec()));
+        self
+    }
+
+    /// Whoever acts next, after the bids. Defaults to the first seat.
+    pub fn current_player(mut self, id: Uuid) -> Self {
+        self.current_player = Some(id);
+        self
+    }
+
+    /// Raises already made this round, as (count, face), oldest first.
+    /// They are made in turn by the seats leading up to the current player.
+    pub fn bids(mut self, bids: &[(usize, u8)]) -> Self {
+        self.bids = bids.to_vec();
+        self
+    }
+
+    /// Dice everyone gets back for a new game. Defaults to the biggest hand.
+    pub fn starting_dice(mut self, starting_dice: u8) -> Self {
+        self.starting_dice = Some(starting_dice);
+        self
+    }
+
+    pub fn rules(mut self, rules: RuleVariant) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Seed for every roll after the scenario
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn build(self) -> Result<LiarsDiceGame, &'static str> {
+        let mut players = BTreeMap::new();
+        for (id, faces) in &self.players {
+            let dice = faces
+                .iter()
+                .map(|face| Dice::new(*face))
+                .collect::<Option<Vec<Dice>>>()
+                .ok_or("Dice faces must be between 1 and 6")?;
+            let player = LiarsDicePlayer {
+                id: *id,
+                dice,
+                last_call: None,
+            };
+            if players.insert(*id, player).is_some() {
+                return Err("Player IDs must be unique");
+            }
+        }
+        let current_players: Vec<Uuid> = self
+            .players
+            .iter()
+            .filter(|(_, faces)| !faces.is_empty())
+            .map(|(id, _)| *id)
+            .collect();
+        if current_players.len() < 2 {
+            return Err("At least two players need dice");
+        }
+        let current_player = self.current_player.unwrap_or(current_players[0]);
+        let Some(current_seat) = current_players.iter().position(|id| *id == current_player) else {
+            return Err("Current player must have dice");
+        };
+        let biggest_hand = self.players.iter().map(|(_, faces)| faces.len()).max();
+        let starting_dice = self
+            .starting_dice
+            .unwrap_or(biggest_hand.unwrap_or(0) as u8);
+        if starting_dice == 0 {
+            return Err("Starting dice must be at least one");
+        }
+        // Bids were made by the seats before the current player, so start that far back
+        let seats = current_players.len();
+        let first_bidder = (current_seat + seats - self.bids.len() % seats) % seats;
+        let mut game = LiarsDiceGame {
+            rng: ChaCha12Rng::seed_from_u64(self.seed),
+            starting_dice,
+            original_player_order: self.players.iter().map(|(id, _)| *id).collect(),
+            seating: self.players.iter().map(|(id, _)| *id).collect(),
+            current_player: Some(current_players[first_bidder]),
+            current_players,
+            players,
+            history: vec![],
+            rules: self.rules,
+            explicit_chance: false,
+            pending_rolls: vec![],
+        };
+        game.record_round_start();
+        for (count, face) in self.bids {
+            let value = Dice::new(face).ok_or("Dice faces must be between 1 and 6")?;
+            game.apply_call(DiceCall::Increase { count, value })?;
+        }
+        Ok(game)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{CallOutcome, Dice, DiceCall, LiarsDiceGame};
+use crate::{CallOutcome, Dice, DiceCall, GameEvent, LiarsDiceGame};
 use rand::seq::IndexedRandom;
 use std::collections::BTreeMap;
 use uuid::Uuid;
@@ -8,6 +8,7 @@ pub enum LiarsDiceState<'a> {
     PlayerTurn(PlayerTurnState<'a>),
     GameComplete(GameCompleteState<'a>),
     Start(GameStartState<'a>),
+    Chance(ChanceNode<'a>),
 }
 
 #[derive(Debug)]
@@ -20,9 +21,9 @@ impl PlayerTurnState<'_> {
     pub fn propose_choice(&self, call: DiceCall) -> Result<ConfirmChoice, &'static str> {
         // Find the previous call
         let previous_calls = self.game.previous_calls();
-        let (_, _, previous) = previous_calls.first().take().unwrap();
+        let (_, _, previous) = previous_calls.first().unwrap();
         match (previous, call) {
-            (None, DiceCall::Increase { count, value }) => Ok(ConfirmChoice(call)),
+            (None, DiceCall::Increase { .. }) => Ok(ConfirmChoice(call)),
             (None, _) => Err("First call must be an increase"),
             (
                 Some(DiceCall::Increase {
@@ -31,16 +32,19 @@ impl PlayerTurnState<'_> {
                 }),
                 _,
             ) => match call {
-                DiceCall::Increase { count, value } => match value.0 == prev_val.0 {
-                    true => match count > *prev_count {
+                DiceCall::Increase { count, value } => {
+                    match self
+                        .game
+                        .rules
+                        .is_raise((*prev_count, *prev_val), (count, value))
+                    {
                         true => Ok(ConfirmChoice(call)),
-                        false => Err("New count must be higher than previous"),
-                    },
-                    false => match count > *prev_count {
-                        true => Ok(ConfirmChoice(call)),
-                        false => Err("New count must be higher than previous"),
-                    },
-                },
+                        false => Err("New bid must be higher than previous"),
+                    }
+                }
+                DiceCall::SpotOn if !self.game.rules.allows_spot_on() => {
+                    Err("SpotOn is not played under these rules")
+                }
                 _ => Ok(ConfirmChoice(call)),
             },
             (Some(_), _) => unreachable!("Previous call should have been final"),
@@ -52,6 +56,10 @@ pub struct ConfirmChoice(DiceCall);
 
 impl ConfirmChoice {
     pub fn confirm(self, state: PlayerTurnState) -> Option<CallOutcome> {
+        state.game.history.push(GameEvent::Called {
+            player_id: state.player_id,
+            call: self.0,
+        });
         match self.0 {
             DiceCall::Increase { count, value } => {
                 let player_id = state.game.current_player.unwrap();
@@ -80,9 +88,9 @@ impl ConfirmChoice {
                     DiceCall::Increase { count, value } => (count, value),
                     _ => unreachable!(),
                 };
-                let actual_count = *tally.get(&prev_val).unwrap();
-                let current_player_id = state.game.current_player.clone().unwrap();
-                match call {
+                let actual_count = state.game.rules.count_matching(&tally, prev_val);
+                let current_player_id = state.game.current_player.unwrap();
+                let correct_call = match call {
                     DiceCall::Bullshit => {
                         let correct_call = actual_count < prev_count;
                         let next_player = state.game.pick_roller_or_next(&current_player_id);
@@ -97,12 +105,7 @@ impl ConfirmChoice {
                             }
                         };
                         next_player.set_correct_player(state.game);
-                        state.game.start_next_round();
-                        Some(CallOutcome {
-                            player_dices,
-                            tally,
-                            correct_call,
-                        })
+                        correct_call
                     }
                     DiceCall::SpotOn => {
                         let correct_call = actual_count == prev_count;
@@ -119,13 +122,7 @@ impl ConfirmChoice {
                                 for player_id in everyone_else {
                                     state.game.remove_dice_from_player(&player_id);
                                 }
-                                state.game.start_next_round();
                                 // Caller goes again
-                                Some(CallOutcome {
-                                    player_dices,
-                                    tally,
-                                    correct_call,
-                                })
                             }
                             false => {
                                 // Caller loses a dice
@@ -133,18 +130,25 @@ impl ConfirmChoice {
                                     state.game.pick_roller_or_next(&current_player_id);
                                 state.game.remove_dice_from_player(&state.player_id);
                                 next_player.set_correct_player(state.game);
-                                Some(CallOutcome {
-                                    player_dices,
-                                    tally,
-                                    correct_call,
-                                })
                             }
                         }
+                        correct_call
                     }
                     _ => {
                         unreachable!()
                     }
-                }
+                };
+                let outcome = CallOutcome {
+                    player_dices,
+                    tally,
+                    correct_call,
+                };
+                state
+                    .game
+                    .history
+                    .push(GameEvent::Revealed(outcome.clone()));
+                state.game.start_next_round();
+                Some(outcome)
             }
         }
     }
@@ -156,6 +160,13 @@ pub struct GameCompleteState<'a> {
     pub(crate) game: &'a mut LiarsDiceGame,
 }
 
+impl GameCompleteState<'_> {
+    /// Hands everyone their dice back and starts over with the same players
+    pub fn play_again(self) {
+        self.game.return_all_dice_for_new_game();
+    }
+}
+
 #[derive(Debug)]
 pub struct GameStartState<'a> {
     pub(crate) game: &'a mut LiarsDiceGame,
@@ -164,12 +175,14 @@ pub struct GameStartState<'a> {
 impl GameStartState<'_> {
     pub fn initialise_game(self) {
         for (_, player) in self.game.players.iter_mut() {
-            player.dice = (0..self.game.starting_dice)
-                .map(|_| Dice::roll(&mut self.game.rng))
-                .collect();
+            player.dice = vec![Dice(1); self.game.starting_dice as usize];
         }
-        self.game.current_players.clear();
-        self.game.current_players = self.game.players.keys().cloned().collect();
+        self.game.history.clear();
+        self.game.pending_rolls.clear();
+        let player_ids: Vec<Uuid> = self.game.players.keys().cloned().collect();
+        self.game.roll_dice(&player_ids);
+        self.game.seating = player_ids.clone();
+        self.game.current_players = player_ids;
         let p = self
             .game
             .current_players
@@ -179,3 +192,30 @@ impl GameStartState<'_> {
         self.game.current_player = Some(p);
     }
 }
+
+/// A die waiting to be rolled, which only comes up in games with explicit chance
+#[derive(Debug)]
+pub struct ChanceNode<'a> {
+    pub(crate) game: &'a mut LiarsDiceGame,
+    pub player_id: Uuid,
+    /// Which of the player's dice is being rolled
+    pub die_index: usize,
+}
+
+impl ChanceNode<'_> {
+    /// Every face the die can land on, with its probability
+    pub fn outcomes(&self) -> Vec<(Dice, f64)> {
+        (1..=6).map(|value| (Dice(value), 1.0 / 6.0)).collect()
+    }
+
+    pub fn resolve(self, outcome: Dice) {
+        self.game.resolve_roll(outcome);
+    }
+
+    /// Rolls the die with the game's own rng
+    pub fn sample(self) -> Dice {
+        let outcome = Dice::roll(&mut self.game.rng);
+        self.game.resolve_roll(outcome);
+        outcome
+    }
+}
@@ -0,0 +1,258 @@
+//! Strategies stored as a table from information set to action probabilities,
+//! so solvers, agents and analysis tools can share them.
+use crate::encoding::{decode_call, encode_call};
+use crate::protocol::{format_call, parse_call};
+use crate::{Dice, DiceCall, LiarsDiceGame};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const BINARY_MAGIC: &[u8; 4] = b"LDTS";
+const BINARY_VERSION: u8 = 1;
+
+/// What a player knows when it's their turn: their own dice, sorted so the order they were rolled in
+/// doesn't matter, and the bids made so far this round.
+/// Written as the faces then the bids, as in `1135 2x3 3x5`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InfoSetKey {
+    dice: Vec<Dice>,
+    /// Action ids, as numbered by [`crate::encoding`]
+    bids: Vec<usize>,
+}
+
+impl InfoSetKey {
+    pub fn new(dice: &[Dice], bids: &[DiceCall]) -> Self {
+        let mut dice = dice.to_vec();
+        dice.sort();
+        Self {
+            dice,
+            bids: bids.iter().map(encode_call).collect(),
+        }
+    }
+
+    /// The key for `player_id` in the round in progress
+    pub fn from_game(game: &LiarsDiceGame, player_id: &Uuid) -> Self {
+        Self::new(&game.player_dices()[player_id], &game.round_calls())
+    }
+
+    pub fn dice(&self) -> &[Dice] {
+        &self.dice
+    }
+
+    pub fn bids(&self) -> Vec<DiceCall> {
+        self.bids
+            .iter()
+            .map(|action| decode_call(*action))
+            .collect()
+    }
+}
+
+impl Display for InfoSetKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for die in &self.dice {
+            write!(f, "{}", die.0)?;
+        }
+        for bid in self.bids() {
+            write!(f, " {}", format_call(&bid))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InfoSetKey {
+    type Err = &'static str;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let mut words = key.split_whitespace();
+        let dice = words
+            .next()
+            .unwrap_or("")
+            .chars()
+            .map(|c| c.to_digit(10).and_then(|value| Dice::new(value as u8)))
+            .collect::<Option<Vec<Dice>>>()
+            .ok_or("Dice faces must be between 1 and 6")?;
+        let bids = words
+            .map(|bid| parse_call(bid).map_err(|_| "Could not read bid"))
+            .collect::<Result<Vec<DiceCall>, _>>()?;
+        Ok(Self::new(&dice, &bids))
+    }
+}
+
+/// Action probabilities for every information set that has been solved
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TabularStrategy {
+    table: BTreeMap<InfoSetKey, Vec<(DiceCall, f32)>>,
+}
+
+impl TabularStrategy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the policy for an information set, kept in action order
+    pub fn insert(&mut self, key: InfoSetKey, mut policy: Vec<(DiceCall, f32)>) {
+        policy.sort_by_key(|(call, _)| encode_call(call));
+        self.table.insert(key, policy);
+    }
+
+    pub fn get(&self, key: &InfoSetKey) -> Option<&[(DiceCall, f32)]> {
+        self.table.get(key).map(|policy| policy.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&InfoSetKey, &[(DiceCall, f32)])> {
+        self.table
+            .iter()
+            .map(|(key, policy)| (key, policy.as_slice()))
+    }
+
+    /// An object from each key to an object from call to probability:
+    /// `{"1135 2x3": {"3x3": 0.25, "liar": 0.75}}`
+    pub fn to_json(&self) -> String {
+        let table: BTreeMap<String, BTreeMap<String, f32>> = self
+            .table
+            .iter()
+            .map(|(key, policy)| {
+                let policy = policy
+                    .iter()
+                    .map(|(call, probability)| (format_call(call), *probability))
+                    .collect();
+                (key.to_string(), policy)
+            })
+            .collect();
+        serde_json::to_string(&table).expect("String keys should always serialize")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, &'static str> {
+        let table: BTreeMap<String, BTreeMap<String, f32>> =
+            serde_json::from_str(json).map_err(|_| "Strategy must be an object of objects")?;
+        let mut strategy = Self::new();
+        for (key, policy) in table {
+            let policy = policy
+                .into_iter()
+                .map(|(call, probability)| {
+                    parse_call(&call)
+                        .map(|call| (call, probability))
+                        .map_err(|_| "Could not read call")
+                })
+                .collect::<Result<_, _>>()?;
+            strategy.insert(key.parse()?, policy);
+        }
+        Ok(strategy)
+    }
+
+    /// `LDTS`, a version byte and the number of keys, then for each key its dice count, faces,
+    /// bid count, bids, action count and every action with its probability.
+    /// Dice and bid counts are single bytes, the key count u32, action counts and actions u16
+    /// and probabilities f32, all little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = BINARY_MAGIC.to_vec();
+        out.push(BINARY_VERSION);
+        out.extend((self.table.len() as u32).to_le_bytes());
+        for (key, policy) in &self.table {
+            out.push(key.dice.len() as u8);
+            out.extend(key.dice.iter().map(|die| die.0));
+            out.push(key.bids.len() as u8);
+            for bid in &key.bids {
+                out.extend((*bid as u16).to_le_bytes());
+            }
+            out.extend((policy.len() as u16).to_le_bytes());
+            for (call, probability) in policy {
+                out.extend((encode_call(call) as u16).to_le_bytes());
+                out.extend(probability.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut reader = ByteReader(bytes);
+        if reader.take(4)? != BINARY_MAGIC {
+            return Err("Not a strategy table");
+        }
+        if reader.u8()? != BINARY_VERSION {
+            return Err("Unsupported strategy table version");
+        }
+        let keys = u32::from_le_bytes(reader.take(4)?.try_into().unwrap());
+        let mut strategy = Self::new();
+        for _ in 0..keys {
+            let dice_count = reader.u8()? as usize;
+            let dice = reader
+                .take(dice_count)?
+                .iter()
+                .map(|face| Dice::new(*face))
+                .collect::<Option<Vec<Dice>>>()
+                .ok_or("Dice faces must be between 1 and 6")?;
+            let bid_count = reader.u8()?;
+            let bids = (0..bid_count)
+                .map(|_| reader.u16().map(|action| action as usize))
+                .collect::<Result<Vec<usize>, _>>()?;
+            let action_count = reader.u16()?;
+            let mut policy = vec![];
+            for _ in 0..action_count {
+                let action = reader.u16()? as usize;
+                let probability = f32::from_le_bytes(reader.take(4)?.try_into().unwrap());
+                policy.push((decode_call(action), probability));
+            }
+            strategy.insert(InfoSetKey { dice, bids }, policy);
+        }
+        // Anything after the last entry means the file was cut short or something was tacked on
+        if !reader.0.is_empty() {
+            return Err("Strategy table has bytes after its last entry");
+        }
+        Ok(strategy)
+    }
+
+    /// Saves as JSON when the path ends in `.json`, and in the binary format otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        match is_json(path) {
+            true => std::fs::write(path, self.to_json()),
+            false => std::fs::write(path, self.to_bytes()),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let invalid = |e| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        match is_json(path) {
+            true => Self::from_json(&std::fs::read_to_string(path)?).map_err(invalid),
+            false => Self::from_bytes(&std::fs::read(path)?).map_err(invalid),
+        }
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], &'static str> {
+        if self.0.len() < count {
+            return Err("Strategy table ends early");
+        }
+        let (taken, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, &'static str> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+}
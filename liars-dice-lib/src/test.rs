@@ -1,8 +1,25 @@
+use crate::agent::{
+    Agent, IsmctsAgent, IsmctsConfig, NeuralAgent, NeuralNetwork, RandomAgent, Selection,
+    TabularAgent,
+};
+use crate::belief::BeliefModel;
+use crate::cfr::{DeepCfr, DeepCfrConfig, ReservoirBuffer, ZeroAdvantages};
+use crate::dataset::{JsonlWriter, SelfPlayConfig, self_play};
+use crate::encoding::{ObservationEncoder, decode_call, encode_call};
+use crate::openspiel::{OpenSpielGame, TERMINAL_PLAYER_ID};
+use crate::profile::PlayerProfiles;
+use crate::protocol::{ProcessAgent, RefereeMessage, parse_call};
+#[cfg(feature = "python")]
+use crate::python::PyLiarsDiceEnv;
+use crate::record::GameRecord;
+use crate::scenario::ScenarioBuilder;
 use crate::state::{GameCompleteState, GameStartState, LiarsDiceState, PlayerTurnState};
-use crate::{Dice, LiarsDiceGame};
+use crate::strategy::{InfoSetKey, TabularStrategy};
+use crate::tournament::{Tournament, TournamentConfig, TournamentFormat, play_game};
+use crate::{Dice, DiceCall, GameEvent, LiarsDiceGame, RuleVariant};
 use rand::SeedableRng;
 use rand_chacha::ChaCha12Rng;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
 #[test]
@@ -65,3 +82,1037 @@ impl<'a> LiarsDiceState<'a> {
         }
     }
 }
+
+fn started_game(seed: u64, starting_dice: u8, player_count: usize) -> (LiarsDiceGame, Vec<Uuid>) {
+    let players: Vec<Uuid> = (0..player_count)
+        .map(|i| Uuid::from_u128(i as u128 + 1))
+        .collect();
+    let mut game = LiarsDiceGame::new(ChaCha12Rng::seed_from_u64(seed), starting_dice, &players);
+    game.get_state().expect_start().unwrap().initialise_game();
+    (game, players)
+}
+
+fn bid(count: usize, face: u8) -> DiceCall {
+    DiceCall::Increase {
+        count,
+        value: Dice(face),
+    }
+}
+
+#[test]
+fn test_random_agents_finish_game() {
+    let (mut game, _) = started_game(7, 3, 3);
+    let mut agent = RandomAgent::new(ChaCha12Rng::seed_from_u64(8));
+    while !game.is_complete() {
+        let player_id = game.current_player().unwrap();
+        let call = agent.choose_call(&game, player_id);
+        game.apply_call(call).unwrap();
+    }
+    let winner = game.winner().unwrap();
+    assert_eq!(
+        game.get_state().expect_complete().unwrap().winner_id,
+        winner
+    );
+}
+
+#[test]
+fn test_ismcts_picks_legal_call_without_touching_game() {
+    let (mut game, _) = started_game(11, 2, 2);
+    let first = game.current_player().unwrap();
+    game.apply_call(DiceCall::Increase {
+        count: 3,
+        value: Dice(6),
+    })
+    .unwrap();
+    let player_id = game.current_player().unwrap();
+    assert_ne!(first, player_id);
+    let before = game.player_dices();
+    let mut agent = IsmctsAgent::new(
+        IsmctsConfig {
+            iterations: 200,
+            ..IsmctsConfig::default()
+        },
+        ChaCha12Rng::seed_from_u64(12),
+        RandomAgent::new(ChaCha12Rng::seed_from_u64(13)),
+    );
+    let call = agent.choose_call(&game, player_id);
+    assert!(game.legal_calls().contains(&call));
+    assert_eq!(game.player_dices(), before);
+}
+
+struct AllSixes;
+
+impl BeliefModel for AllSixes {
+    fn likelihood(&self, _game: &LiarsDiceGame, _player_id: &Uuid, dice: &[Dice]) -> f64 {
+        match dice.iter().all(|d| *d == Dice(6)) {
+            true => 1.0,
+            false => 0.0,
+        }
+    }
+}
+
+#[test]
+fn test_determinize_keeps_observer_view() {
+    let (mut game, players) = started_game(21, 2, 3);
+    let bid = DiceCall::Increase {
+        count: 2,
+        value: Dice(3),
+    };
+    game.apply_call(bid).unwrap();
+    let observer = players[0];
+    let mut rng = ChaCha12Rng::seed_from_u64(22);
+    let sampled = game.determinize_with(&observer, &AllSixes, &mut rng);
+    assert_eq!(sampled.previous_calls(), game.previous_calls());
+    assert_eq!(sampled.current_players(), game.current_players());
+    let (real, sampled) = (game.player_dices(), sampled.player_dices());
+    for id in &players {
+        match *id == observer {
+            true => assert_eq!(sampled[id], real[id]),
+            false => assert_eq!(sampled[id], vec![Dice(6), Dice(6)]),
+        }
+    }
+}
+
+/// Only likes the hand it can see, which would be the real one if the game gave it away
+struct Peeking;
+
+impl BeliefModel for Peeking {
+    fn likelihood(&self, game: &LiarsDiceGame, player_id: &Uuid, dice: &[Dice]) -> f64 {
+        match game.player_dices()[player_id] == dice {
+            true => 1.0,
+            false => 0.0,
+        }
+    }
+}
+
+#[test]
+fn test_determinize_hides_real_dice_from_belief() {
+    let (me, them) = (Uuid::from_u128(1), Uuid::from_u128(2));
+    let game = ScenarioBuilder::new()
+        .player(me, &[1, 2])
+        .player(them, &[6, 6, 6, 6])
+        .bids(&[(1, 6)])
+        .build()
+        .unwrap();
+    for seed in 0..20 {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let sampled = game.determinize_with(&me, &Peeking, &mut rng);
+        assert_ne!(sampled.player_dices()[&them], vec![Dice(6); 4]);
+    }
+}
+
+#[test]
+fn test_tournament_is_reproducible() {
+    let run = || {
+        let mut tournament = Tournament::new(TournamentConfig {
+            format: TournamentFormat::Swiss { rounds: 2 },
+            games_per_match: 4,
+            starting_dice: 2,
+            seed: 5,
+        });
+        for name in ["a", "b", "c"] {
+            tournament.register(name, |rng| {
+                Box::new(RandomAgent::new(rng)) as Box<dyn Agent>
+            });
+        }
+        tournament.run()
+    };
+    let (first, second) = (run(), run());
+    assert_eq!(first.to_string(), second.to_string());
+    let games: usize = first.standings.iter().map(|s| s.games).sum();
+    // Two rounds of a single pairing, each game counted for both players
+    assert_eq!(games, 2 * 4 * 2);
+    let wins: usize = first.standings.iter().map(|s| s.wins).sum();
+    assert_eq!(wins, 2 * 4);
+}
+
+#[test]
+fn test_history_records_calls_and_reveals() {
+    let (mut game, _) = started_game(3, 2, 2);
+    let bidder = game.current_player().unwrap();
+    let bid = DiceCall::Increase {
+        count: 1,
+        value: Dice(2),
+    };
+    game.apply_call(bid).unwrap();
+    let challenger = game.current_player().unwrap();
+    let outcome = game.apply_call(DiceCall::Bullshit).unwrap().unwrap();
+    let history = game.history();
+    assert!(matches!(history[0], GameEvent::RoundStarted { .. }));
+    assert_eq!(
+        history[1..3],
+        [
+            GameEvent::Called {
+                player_id: bidder,
+                call: bid
+            },
+            GameEvent::Called {
+                player_id: challenger,
+                call: DiceCall::Bullshit
+            },
+        ]
+    );
+    assert_eq!(history[3], GameEvent::Revealed(outcome));
+    assert_eq!(
+        history[4],
+        GameEvent::RoundStarted {
+            player_dices: game.player_dices()
+        }
+    );
+}
+
+#[test]
+fn test_protocol_messages_round_trip() {
+    let messages = [
+        RefereeMessage::NewGame {
+            you: Uuid::from_u128(1),
+            players: vec![Uuid::from_u128(1), Uuid::from_u128(2)],
+        },
+        RefereeMessage::Dice(vec![Dice(3), Dice(5), Dice(5)]),
+        RefereeMessage::Call {
+            player_id: Uuid::from_u128(2),
+            call: DiceCall::Increase {
+                count: 4,
+                value: Dice(5),
+            },
+        },
+        RefereeMessage::Call {
+            player_id: Uuid::from_u128(1),
+            call: DiceCall::SpotOn,
+        },
+        RefereeMessage::Reveal(BTreeMap::from([
+            (Uuid::from_u128(1), vec![Dice(3), Dice(5)]),
+            (Uuid::from_u128(2), vec![Dice(1)]),
+        ])),
+        RefereeMessage::YourTurn,
+        RefereeMessage::Quit,
+    ];
+    for message in messages {
+        assert_eq!(
+            message.to_string().parse::<RefereeMessage>().unwrap(),
+            message
+        );
+    }
+    assert!(parse_call("7x0").is_err());
+    assert_eq!(parse_call(" LIAR ").unwrap(), DiceCall::Bullshit);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_process_agent_fails_after_bad_answer() {
+    let (game, _) = started_game(4, 2, 2);
+    let player_id = game.current_player().unwrap();
+    let mut command = std::process::Command::new("sh");
+    command.args([
+        "-c",
+        "while read line; do [ \"$line\" = yourturn ] && echo 2x3; done",
+    ]);
+    let mut agent = ProcessAgent::spawn(command, std::time::Duration::from_secs(5)).unwrap();
+    let call = agent.choose_call(&game, player_id);
+    assert_eq!(
+        call,
+        DiceCall::Increase {
+            count: 2,
+            value: Dice(3)
+        }
+    );
+    assert!(agent.error().is_none());
+
+    let mut command = std::process::Command::new("sh");
+    command.args([
+        "-c",
+        "while read line; do [ \"$line\" = yourturn ] && echo nonsense; done",
+    ]);
+    let mut agent = ProcessAgent::spawn(command, std::time::Duration::from_secs(5)).unwrap();
+    agent.choose_call(&game, player_id);
+    assert!(agent.error().is_some());
+    assert_eq!(agent.failure(), Some("Bot did not answer with a call"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_process_agent_exiting_mid_game_forfeits() {
+    let seats = [Uuid::from_u128(1), Uuid::from_u128(2)];
+    let mut game = LiarsDiceGame::new(ChaCha12Rng::seed_from_u64(5), 2, &seats);
+    let mut command = std::process::Command::new("sh");
+    command.args([
+        "-c",
+        "while read line; do [ \"$line\" = yourturn ] && echo 1x2 && exit; done",
+    ]);
+    let bot = ProcessAgent::spawn(command, std::time::Duration::from_secs(5)).unwrap();
+    let mut agents: BTreeMap<Uuid, Box<dyn Agent>> = BTreeMap::from([
+        (seats[0], Box::new(bot) as Box<dyn Agent>),
+        (
+            seats[1],
+            Box::new(RandomAgent::new(ChaCha12Rng::seed_from_u64(6))),
+        ),
+    ]);
+    // Nobody can be knocked out in the first round, so the bot is asked again after it exits
+    let (offender, _) = play_game(&mut game, &mut agents).unwrap_err();
+    assert_eq!(offender, seats[0]);
+    assert!(!game.is_complete());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_process_agent_hears_players_in_seating_order() {
+    let seats = [4, 1, 3, 2].map(Uuid::from_u128);
+    let game = ScenarioBuilder::new()
+        .player(seats[0], &[1, 2])
+        .player(seats[1], &[3, 4])
+        .player(seats[2], &[5, 6])
+        .player(seats[3], &[])
+        .current_player(seats[2])
+        .bids(&[(1, 2), (2, 2)])
+        .build()
+        .unwrap();
+    let newgame = RefereeMessage::NewGame {
+        you: seats[2],
+        players: seats.to_vec(),
+    };
+    // The bot only makes a call if it was told the players in the order they sit
+    let script = format!(
+        "while read line; do case \"$line\" in newgame*) players=\"$line\";; \
+         yourturn) [ \"$players\" = \"{newgame}\" ] && echo liar || echo wrong;; esac; done"
+    );
+    let mut command = std::process::Command::new("sh");
+    command.args(["-c", &script]);
+    let mut agent = ProcessAgent::spawn(command, std::time::Duration::from_secs(5)).unwrap();
+    assert_eq!(agent.choose_call(&game, seats[2]), DiceCall::Bullshit);
+    assert!(agent.error().is_none());
+}
+
+#[test]
+fn test_encoding_round_trips_actions() {
+    let (mut game, players) = started_game(9, 2, 3);
+    let encoder = ObservationEncoder::new(3, 2);
+    for action in 0..encoder.action_count() {
+        assert_eq!(encode_call(&decode_call(action)), action);
+    }
+    let mask = encoder.legal_action_mask(&game);
+    assert_eq!(mask[0], 0.0);
+    assert_eq!(mask.iter().sum::<f32>(), 36.0);
+    game.apply_call(DiceCall::Increase {
+        count: 2,
+        value: Dice(4),
+    })
+    .unwrap();
+    let observation = encoder.encode(&game, &players[0]);
+    assert_eq!(observation.len(), encoder.size());
+    let bid = &observation[encoder.size() - 8..];
+    assert_eq!(bid, [1.0, 2.0 / 6.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_encoding_lists_seats_from_observer_in_seating_order() {
+    // Seated 1, 3, 2, so the seat after the observer isn't the next id
+    let seats = [1, 3, 2].map(Uuid::from_u128);
+    let game = ScenarioBuilder::new()
+        .player(seats[0], &[1])
+        .player(seats[1], &[1, 2, 3])
+        .player(seats[2], &[1, 2])
+        .current_player(seats[1])
+        .bids(&[(1, 5)])
+        .build()
+        .unwrap();
+    let encoder = ObservationEncoder::new(3, 3);
+    let observation = encoder.encode(&game, &seats[0]);
+    assert_eq!(observation[6..9], [1.0 / 3.0, 3.0 / 3.0, 2.0 / 3.0]);
+    // Only the observer has called, and their block comes first
+    assert_eq!(observation[9], 1.0);
+    assert_eq!(observation[17], 0.0);
+    let observation = encoder.encode(&game, &seats[2]);
+    assert_eq!(observation[6..9], [2.0 / 3.0, 1.0 / 3.0, 3.0 / 3.0]);
+    assert_eq!(observation[6 + 3 + 8], 1.0);
+}
+
+#[cfg(feature = "python")]
+#[test]
+fn test_python_env_resets_and_steps() {
+    let mut env = PyLiarsDiceEnv::new(2, 1, 7).unwrap();
+    let encoder = ObservationEncoder::new(2, 1);
+    let (observation, mask) = env.start_game(Some(7));
+    assert_eq!(observation.len(), encoder.size());
+    // Nothing to challenge yet, so only the twelve raises are legal
+    assert_eq!(mask.len(), encoder.action_count());
+    assert_eq!(mask[..2], [0.0, 0.0]);
+    assert_eq!(mask.iter().sum::<f32>(), 12.0);
+    assert!(env.play_action(0).is_err());
+    assert!(env.play_action(encoder.action_count()).is_err());
+
+    let (_, mask, rewards, done) = env.play_action(2).unwrap();
+    assert_eq!(rewards, [0.0, 0.0]);
+    assert!(!done);
+    assert_eq!(mask[..3], [1.0, 1.0, 0.0]);
+    // With one die each, the challenge ends the game
+    let (_, mask, mut rewards, done) = env.play_action(0).unwrap();
+    assert!(done);
+    assert_eq!(mask.iter().sum::<f32>(), 0.0);
+    rewards.sort_by(f32::total_cmp);
+    assert_eq!(rewards, [-1.0, 1.0]);
+    assert!(env.play_action(2).is_err());
+
+    // The same seed deals the same game, and no seed moves on to the next one
+    assert_eq!(env.start_game(Some(7)), env.start_game(Some(7)));
+    let next = env.start_game(None);
+    assert_eq!(next, env.start_game(Some(8)));
+}
+
+#[test]
+fn test_openspiel_single_round() {
+    let game = OpenSpielGame::new(2, 2);
+    let mut state = game.new_initial_state();
+    // Seat 0 rolls 3 and 5, seat 1 rolls 5 and 6
+    for face in [2, 4, 4, 5] {
+        assert!(state.is_chance_node());
+        assert_eq!(state.chance_outcomes().len(), 6);
+        state.apply_action(face).unwrap();
+    }
+    assert_eq!(state.current_player(), 0);
+    assert_eq!(state.legal_actions().len(), 24);
+    // 2-5, then seat 1 raises to 3-5, which seat 0 calls
+    state.apply_action(10).unwrap();
+    assert_eq!(state.current_player(), 1);
+    assert!(state.apply_action(9).is_err());
+    state.apply_action(16).unwrap();
+    assert_eq!(state.information_state_string(0), "35 2-5 3-5");
+    state.apply_action(24).unwrap();
+    assert!(state.is_terminal());
+    // Two fives and a wild six make three, so the bid holds and seat 0 loses the challenge
+    assert_eq!(state.returns(), vec![-1.0, 1.0]);
+    assert_eq!(state.to_string(), "35 56 2-5 3-5 Liar");
+    let tensor = state.information_state_tensor(1);
+    assert_eq!(tensor.len(), game.information_state_tensor_size());
+    assert_eq!(tensor[..2], [0.0, 1.0]);
+    assert_eq!(tensor[2 + 4], 1.0);
+    assert_eq!(tensor[2 + 6 + 5], 1.0);
+    assert_eq!(tensor[14 + 24], 1.0);
+}
+
+#[test]
+fn test_openspiel_bid_order_and_returns() {
+    // Three players with a die each roll 6, 3 and 2
+    let game = OpenSpielGame::new(3, 1);
+    let mut state = game.new_initial_state();
+    for face in [5, 2, 1] {
+        state.apply_action(face).unwrap();
+    }
+    assert_eq!(game.max_game_length(), 19);
+    // 1-3, then 1-4 raises the face without the count, then 2-3, and seat 0 calls Liar
+    for action in [2, 3, 8] {
+        state.apply_action(action).unwrap();
+    }
+    assert_eq!(state.legal_actions(), (9..=18).collect::<Vec<_>>());
+    assert!(state.apply_action(8).is_err());
+    state.apply_action(18).unwrap();
+    assert_eq!(state.history(), [5, 2, 1, 2, 3, 8, 18]);
+    assert_eq!(state.to_string(), "6 3 2 1-3 1-4 2-3 Liar");
+    // The 3 and the wild 6 cover 2-3, so seat 2's bid wins and the challenger loses
+    assert_eq!(state.returns(), vec![-1.0, 0.0, 1.0]);
+    assert_eq!(state.current_player(), TERMINAL_PLAYER_ID);
+}
+
+#[test]
+fn test_openspiel_rules_raise_by_face_and_make_sixes_wild() {
+    let (a, b) = (Uuid::from_u128(1), Uuid::from_u128(2));
+    let mut game = ScenarioBuilder::new()
+        .player(a, &[3, 6])
+        .player(b, &[2, 5])
+        .rules(RuleVariant::OpenSpiel)
+        .current_player(b)
+        .bids(&[(2, 3)])
+        .build()
+        .unwrap();
+    let legal = game.legal_calls();
+    assert_eq!(legal[..2], [DiceCall::Bullshit, bid(2, 4)]);
+    assert!(!legal.contains(&DiceCall::SpotOn));
+    assert!(!legal.contains(&bid(2, 2)));
+    assert!(game.apply_call(DiceCall::SpotOn).is_err());
+    assert!(game.apply_call(bid(2, 2)).is_err());
+    // The 3 and the wild 6 make two threes, so the challenge is wrong
+    let outcome = game.apply_call(DiceCall::Bullshit).unwrap().unwrap();
+    assert!(!outcome.correct_call);
+    assert_eq!(RuleVariant::OpenSpiel.match_chance(Dice(6)), 1.0 / 6.0);
+    assert!(!RuleVariant::OpenSpiel.matches(Dice(1), Dice(6)));
+}
+
+#[test]
+fn test_explicit_chance_nodes() {
+    let players = [Uuid::from_u128(1), Uuid::from_u128(2)];
+    let mut game =
+        LiarsDiceGame::new(ChaCha12Rng::seed_from_u64(1), 2, &players).with_explicit_chance(true);
+    game.get_state().expect_start().unwrap().initialise_game();
+    for face in [2, 2, 5, 6] {
+        let LiarsDiceState::Chance(chance) = game.get_state() else {
+            panic!("Dice should be waiting on chance");
+        };
+        let total: f64 = chance.outcomes().iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        chance.resolve(Dice(face));
+    }
+    assert_eq!(
+        game.player_dices(),
+        BTreeMap::from([
+            (players[0], vec![Dice(2), Dice(2)]),
+            (players[1], vec![Dice(5), Dice(6)]),
+        ])
+    );
+    assert_eq!(
+        game.history(),
+        [GameEvent::RoundStarted {
+            player_dices: game.player_dices()
+        }]
+    );
+    game.apply_call(DiceCall::Increase {
+        count: 2,
+        value: Dice(2),
+    })
+    .unwrap();
+    let outcome = game.apply_call(DiceCall::Bullshit).unwrap().unwrap();
+    assert!(!outcome.correct_call);
+    // The challenger lost a die, so three dice need rolling before anyone can call
+    assert!(game.legal_calls().is_empty());
+    for _ in 0..3 {
+        let LiarsDiceState::Chance(chance) = game.get_state() else {
+            panic!("Dice should be waiting on chance");
+        };
+        chance.sample();
+    }
+    assert!(game.get_state().expect_player_turn().is_ok());
+}
+
+fn three_players() -> [Uuid; 3] {
+    [Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3)]
+}
+
+fn scenario(rules: RuleVariant, bids: &[(usize, u8)]) -> LiarsDiceGame {
+    let [a, b, c] = three_players();
+    ScenarioBuilder::new()
+        .player(a, &[1, 1])
+        .player(b, &[3, 6])
+        .player(c, &[4])
+        .current_player(a)
+        .bids(bids)
+        .rules(rules)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_scenario_builder_seats_bids_before_current_player() {
+    let [a, b, c] = three_players();
+    let game = scenario(RuleVariant::Standard, &[(2, 3), (3, 3)]);
+    assert_eq!(game.current_player(), Some(a));
+    assert_eq!(
+        game.previous_calls(),
+        vec![
+            (
+                c,
+                1,
+                Some(DiceCall::Increase {
+                    count: 3,
+                    value: Dice(3)
+                })
+            ),
+            (
+                b,
+                2,
+                Some(DiceCall::Increase {
+                    count: 2,
+                    value: Dice(3)
+                })
+            ),
+            (a, 2, None),
+        ]
+    );
+    assert!(
+        ScenarioBuilder::new()
+            .player(a, &[7])
+            .player(b, &[1])
+            .build()
+            .is_err()
+    );
+    assert!(scenario_err(&[(2, 3), (2, 4)]));
+}
+
+fn scenario_err(bids: &[(usize, u8)]) -> bool {
+    let [a, b, _] = three_players();
+    ScenarioBuilder::new()
+        .player(a, &[1])
+        .player(b, &[1])
+        .bids(bids)
+        .build()
+        .is_err()
+}
+
+#[test]
+fn test_correct_bullshit_knocks_out_bidder() {
+    let [a, b, c] = three_players();
+    let mut game = scenario(RuleVariant::Standard, &[(2, 3), (3, 3)]);
+    let outcome = game.apply_call(DiceCall::Bullshit).unwrap().unwrap();
+    assert!(outcome.correct_call);
+    assert_eq!(game.current_players(), [a, b]);
+    assert_eq!(game.current_player(), Some(a));
+    assert!(game.player_dices()[&c].is_empty());
+}
+
+#[test]
+fn test_wrong_spot_on_costs_caller_and_starts_new_round() {
+    let [a, _, _] = three_players();
+    let mut game = scenario(RuleVariant::Standard, &[(2, 3)]);
+    let outcome = game.apply_call(DiceCall::SpotOn).unwrap().unwrap();
+    assert!(!outcome.correct_call);
+    assert_eq!(game.player_dices()[&a].len(), 1);
+    assert_eq!(game.current_player(), Some(a));
+    assert!(
+        game.previous_calls()
+            .iter()
+            .all(|(_, _, call)| call.is_none())
+    );
+    assert!(!game.legal_calls().contains(&DiceCall::Bullshit));
+}
+
+#[test]
+fn test_correct_spot_on_costs_everyone_else() {
+    let [a, b, c] = three_players();
+    let mut game = scenario(RuleVariant::Standard, &[(1, 3)]);
+    let outcome = game.apply_call(DiceCall::SpotOn).unwrap().unwrap();
+    assert!(outcome.correct_call);
+    assert_eq!(game.current_players(), [a, b]);
+    assert_eq!(game.player_dices()[&b].len(), 1);
+    assert!(game.player_dices()[&c].is_empty());
+    assert_eq!(game.current_player(), Some(a));
+}
+
+#[test]
+fn test_ones_wild_count_towards_bid() {
+    let [a, _, _] = three_players();
+    let mut standard = scenario(RuleVariant::Standard, &[(3, 3)]);
+    assert!(
+        standard
+            .apply_call(DiceCall::Bullshit)
+            .unwrap()
+            .unwrap()
+            .correct_call
+    );
+    let mut wild = scenario(RuleVariant::OnesWild, &[(3, 3)]);
+    assert!(
+        !wild
+            .apply_call(DiceCall::Bullshit)
+            .unwrap()
+            .unwrap()
+            .correct_call
+    );
+    assert_eq!(wild.player_dices()[&a].len(), 1);
+}
+
+fn self_play_config() -> SelfPlayConfig {
+    SelfPlayConfig {
+        games: 3,
+        players: 3,
+        starting_dice: 2,
+        rules: RuleVariant::Standard,
+        seed: 17,
+    }
+}
+
+/// Plays the first legal call, then gives up
+struct GivesUp(bool);
+
+impl Agent for GivesUp {
+    fn choose_call(&mut self, game: &LiarsDiceGame, _player_id: Uuid) -> DiceCall {
+        self.0 = true;
+        game.legal_calls()[0]
+    }
+
+    fn failure(&self) -> Option<&'static str> {
+        self.0.then_some("Gave up")
+    }
+}
+
+#[test]
+fn test_self_play_reports_forfeits() {
+    let config = self_play_config();
+    let mut writer = JsonlWriter::new(vec![]);
+    let factory = |_| Box::new(GivesUp(false)) as Box<dyn Agent>;
+    let error = self_play(&config, factory, &mut writer).unwrap_err();
+    assert!(error.to_string().ends_with("forfeited: Gave up"), "{error}");
+}
+
+#[test]
+fn test_self_play_writes_jsonl_rows() {
+    let config = self_play_config();
+    let mut writer = JsonlWriter::new(vec![]);
+    let factory = |rng| Box::new(RandomAgent::new(rng)) as Box<dyn Agent>;
+    let written = self_play(&config, factory, &mut writer).unwrap();
+    let text = String::from_utf8(writer.into_inner()).unwrap();
+    let rows: Vec<serde_json::Value> = text
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), written);
+    let encoder = config.encoder();
+    for row in &rows {
+        assert_eq!(row["observation"].as_array().unwrap().len(), encoder.size());
+        assert_eq!(
+            row["legal_mask"].as_array().unwrap().len(),
+            encoder.action_count()
+        );
+        let action = row["action"].as_u64().unwrap() as usize;
+        assert_eq!(row["legal_mask"][action], 1.0);
+    }
+    // Every game ends with its last challenge, made on the final turn
+    for game in 0..config.games {
+        let last = rows.iter().rfind(|row| row["game"] == game).unwrap();
+        assert!(last["correct_call"].is_boolean());
+        let winners: BTreeSet<u64> = rows
+            .iter()
+            .filter(|row| row["game"] == game && row["final_return"] == 1.0)
+            .map(|row| row["player"].as_u64().unwrap())
+            .collect();
+        assert_eq!(winners.len(), 1);
+    }
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_self_play_writes_arrow_ipc() {
+    let config = self_play_config();
+    let mut writer = crate::dataset::ArrowIpcWriter::new(vec![], &config.encoder()).unwrap();
+    let factory = |rng| Box::new(RandomAgent::new(rng)) as Box<dyn Agent>;
+    let written = self_play(&config, factory, &mut writer).unwrap();
+    let bytes = writer.into_inner().unwrap();
+    let reader = arrow_ipc::reader::FileReader::try_new(std::io::Cursor::new(bytes), None).unwrap();
+    let batches: Vec<_> = reader.map(|batch| batch.unwrap()).collect();
+    assert_eq!(batches.len(), config.games as usize);
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), written);
+}
+
+#[test]
+fn test_record_round_trips_through_replay() {
+    let (mut game, _) = started_game(11, 2, 3);
+    let mut agent = RandomAgent::new(ChaCha12Rng::seed_from_u64(12));
+    while !game.is_complete() {
+        let player_id = game.current_player().unwrap();
+        let call = agent.choose_call(&game, player_id);
+        game.apply_call(call).unwrap();
+    }
+    let mut record = GameRecord::from_game(&game);
+    record.seed = Some(11);
+    record.rounds[0].calls[0].comment = Some("opening bid".to_string());
+    let parsed: GameRecord = record.to_string().parse().unwrap();
+    assert_eq!(parsed, record);
+    let replayed = parsed.replay().unwrap();
+    assert_eq!(replayed.history(), game.history());
+    assert_eq!(replayed.winner(), game.winner());
+}
+
+#[test]
+fn test_record_replays_players_seated_out_of_id_order() {
+    let seats = [4, 1, 3, 2].map(|i| Uuid::from_u128(i * 1000 + 7));
+    let mut game = LiarsDiceGame::new(ChaCha12Rng::seed_from_u64(31), 2, &seats);
+    game.get_state().expect_start().unwrap().initialise_game();
+    let mut agent = RandomAgent::new(ChaCha12Rng::seed_from_u64(32));
+    // The first game is seated in id order, and the second in the order the players were given
+    for _ in 0..2 {
+        while !game.is_complete() {
+            let player_id = game.current_player().unwrap();
+            let call = agent.choose_call(&game, player_id);
+            game.apply_call(call).unwrap();
+        }
+        let record = GameRecord::from_game(&game);
+        let parsed: GameRecord = record.to_string().parse().unwrap();
+        let replayed = parsed.replay().unwrap();
+        assert_eq!(GameRecord::from_game(&replayed), record);
+        game.get_state().expect_complete().unwrap().play_again();
+    }
+}
+
+#[test]
+fn test_record_parses_annotated_game() {
+    let text = r#"[Rules "Standard"]
+[Seed "42"]
+[StartingDice "2"]
+[Players "A B C"]
+[Winner "B"]
+[Event "Club night"]
+
+; Lines starting with a semicolon are ignored
+R1: A 2x4, B 3x4 {bold}, C 4x6, A LIAR -> [A:24 B:13 C:66] C -1
+R2: A 1x2, B 2x2, C SPOTON -> [A:22 B:51 C:3] A -1 B -1
+R3: C 1x5, A LIAR -> [A:4 B:2 C:5] A -1
+R4: B 1x3, C LIAR -> [B:3 C:1] C -1
+"#;
+    let record: GameRecord = text.parse().unwrap();
+    assert_eq!(record.rounds.len(), 4);
+    assert_eq!(record.rounds[0].calls[1].comment.as_deref(), Some("bold"));
+    assert_eq!(
+        record.tags,
+        vec![("Event".to_string(), "Club night".to_string())]
+    );
+    let game = record.replay().unwrap();
+    assert_eq!(game.winner(), Some(Uuid::from_u128(2)));
+
+    // Claiming the wrong player lost the challenge doesn't replay
+    let wrong = text.replace(
+        "C 4x6, A LIAR -> [A:24 B:13 C:66] C -1",
+        "C 4x6, A LIAR -> [A:24 B:13 C:66] A -1",
+    );
+    let record: GameRecord = wrong.parse().unwrap();
+    assert_eq!(
+        record.replay().unwrap_err(),
+        "Losers don't match the replay"
+    );
+
+    let error = text
+        .replace("B 2x2", "B 2y2")
+        .parse::<GameRecord>()
+        .unwrap_err();
+    assert_eq!(error.line, 10);
+}
+
+/// Safetensors for a network with no hidden layer that always prefers `action`
+fn favours_one_action(encoder: &ObservationEncoder, action: usize) -> Vec<u8> {
+    let weight = vec![0f32; encoder.action_count() * encoder.size()];
+    let mut bias = vec![0f32; encoder.action_count()];
+    bias[action] = 10.0;
+    let bytes =
+        |values: &[f32]| -> Vec<u8> { values.iter().flat_map(|v| v.to_le_bytes()).collect() };
+    let (weight, bias) = (bytes(&weight), bytes(&bias));
+    let tensors = [
+        (
+            "out.weight",
+            safetensors::tensor::TensorView::new(
+                safetensors::Dtype::F32,
+                vec![encoder.action_count(), encoder.size()],
+                &weight,
+            )
+            .unwrap(),
+        ),
+        (
+            "out.bias",
+            safetensors::tensor::TensorView::new(
+                safetensors::Dtype::F32,
+                vec![encoder.action_count()],
+                &bias,
+            )
+            .unwrap(),
+        ),
+    ];
+    safetensors::serialize(tensors, None).unwrap()
+}
+
+#[test]
+fn test_neural_agent_only_plays_legal_calls() {
+    let (mut game, _) = started_game(21, 2, 2);
+    let encoder = ObservationEncoder::new(2, 2);
+    let favourite = DiceCall::Increase {
+        count: 4,
+        value: Dice(6),
+    };
+    let weights = favours_one_action(&encoder, encode_call(&favourite));
+    let sidecar = r#"{"players": 2, "starting_dice": 2, "layers": ["out"]}"#;
+    let network = NeuralNetwork::from_bytes(sidecar, &weights).unwrap();
+    let mut agent = NeuralAgent::new(
+        network.clone(),
+        Selection::Argmax,
+        ChaCha12Rng::seed_from_u64(0),
+    );
+    let player_id = game.current_player().unwrap();
+    assert_eq!(agent.choose_call(&game, player_id), favourite);
+
+    // Once 4x6 has been called it's illegal to call again, so the agent has to pick something else
+    game.apply_call(favourite).unwrap();
+    let player_id = game.current_player().unwrap();
+    let probs = network.action_probs(&game, &player_id);
+    assert!((probs.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+    let mut agent = NeuralAgent::new(network, Selection::Sample, ChaCha12Rng::seed_from_u64(0));
+    for _ in 0..20 {
+        let call = agent.choose_call(&game, player_id);
+        assert!(game.legal_calls().contains(&call));
+    }
+
+    let wrong_game = r#"{"players": 3, "starting_dice": 2, "layers": ["out"]}"#;
+    assert!(NeuralNetwork::from_bytes(wrong_game, &weights).is_err());
+}
+
+#[test]
+fn test_reservoir_buffer_keeps_capacity() {
+    let mut buffer = ReservoirBuffer::new(100);
+    let mut rng = ChaCha12Rng::seed_from_u64(0);
+    for i in 0..10_000 {
+        buffer.push(i, &mut rng);
+    }
+    assert_eq!(buffer.len(), 100);
+    assert_eq!(buffer.seen(), 10_000);
+    // A uniform sample should mostly come from the later pushes
+    assert!(buffer.items().iter().filter(|i| **i >= 5_000).count() > 30);
+}
+
+#[test]
+fn test_recall_encoding_keeps_earlier_bids() {
+    let (game, _) = started_game(9, 2, 2);
+    let encoder = ObservationEncoder::new(2, 2);
+    let raise = |count, face| DiceCall::Increase {
+        count,
+        value: Dice(face),
+    };
+    // The same last call from each seat, reached through a different opening bid
+    let play = |opening| {
+        let mut game = game.clone();
+        for call in [opening, raise(2, 3), raise(3, 4)] {
+            game.apply_call(call).unwrap();
+        }
+        game
+    };
+    let (a, b) = (play(raise(1, 2)), play(raise(1, 5)));
+    let observer = a.current_player().unwrap();
+    assert_eq!(encoder.encode(&a, &observer), encoder.encode(&b, &observer));
+    let recall = encoder.encode_recall(&a, &observer);
+    assert_ne!(recall, encoder.encode_recall(&b, &observer));
+    assert_eq!(recall.len(), encoder.recall_size());
+    assert_eq!(recall[encoder.size()..].iter().sum::<f32>(), 3.0);
+    assert_eq!(recall[encoder.size() + encode_call(&raise(1, 2)) - 2], 1.0);
+    assert_eq!(a.round_calls(), [raise(1, 2), raise(2, 3), raise(3, 4)]);
+}
+
+#[test]
+fn test_deep_cfr_collects_and_saves_samples() {
+    let mut cfr = DeepCfr::new(DeepCfrConfig {
+        players: 2,
+        starting_dice: 1,
+        advantage_capacity: 10_000,
+        strategy_capacity: 10_000,
+        seed: 3,
+    });
+    cfr.run_iteration(1, 4, &ZeroAdvantages);
+    for seat in 0..2 {
+        let buffer = cfr.advantages(seat);
+        assert!(!buffer.is_empty());
+        for sample in buffer.items() {
+            assert_eq!(sample.observation.len(), cfr.encoder().recall_size());
+            for (regret, legal) in sample.target.iter().zip(&sample.legal_mask) {
+                assert!(*legal > 0.0 || *regret == 0.0);
+            }
+        }
+    }
+    for sample in cfr.strategies().items() {
+        assert!((sample.target.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+    }
+
+    let dir = std::env::temp_dir().join(format!("deep-cfr-{}", std::process::id()));
+    cfr.save(&dir).unwrap();
+    let bytes = std::fs::read(dir.join("advantages_0_targets.npy")).unwrap();
+    assert!(bytes.starts_with(b"\x93NUMPY"));
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    let rows = cfr.advantages(0).len();
+    let header = String::from_utf8_lossy(&bytes[10..10 + header_len]);
+    assert!(header.contains(&format!(
+        "'shape': ({rows}, {}),",
+        cfr.encoder().action_count()
+    )));
+    assert_eq!(
+        bytes.len(),
+        10 + header_len + rows * cfr.encoder().action_count() * 4
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_info_set_keys_merge_dice_order() {
+    let bids = [DiceCall::Increase {
+        count: 2,
+        value: Dice(3),
+    }];
+    let key = InfoSetKey::new(&[Dice(5), Dice(1), Dice(3)], &bids);
+    assert_eq!(key, InfoSetKey::new(&[Dice(3), Dice(5), Dice(1)], &bids));
+    assert_eq!(key.to_string(), "135 2x3");
+    assert_eq!("531 2x3".parse::<InfoSetKey>().unwrap(), key);
+
+    let game = scenario(RuleVariant::Standard, &[(2, 3)]);
+    let player_id = game.current_player().unwrap();
+    let key = InfoSetKey::from_game(&game, &player_id);
+    assert_eq!(key.bids(), bids);
+}
+
+#[test]
+fn test_tabular_strategy_round_trips_and_plays() {
+    let game = scenario(RuleVariant::Standard, &[(2, 3)]);
+    let player_id = game.current_player().unwrap();
+    let mut strategy = TabularStrategy::new();
+    strategy.insert(
+        InfoSetKey::from_game(&game, &player_id),
+        vec![(DiceCall::Bullshit, 1.0), (DiceCall::SpotOn, 0.0)],
+    );
+    strategy.insert(
+        "66".parse().unwrap(),
+        vec![
+            (
+                DiceCall::Increase {
+                    count: 2,
+                    value: Dice(6),
+                },
+                0.75,
+            ),
+            (
+                DiceCall::Increase {
+                    count: 3,
+                    value: Dice(6),
+                },
+                0.25,
+            ),
+        ],
+    );
+    assert_eq!(
+        TabularStrategy::from_bytes(&strategy.to_bytes()).unwrap(),
+        strategy
+    );
+    assert_eq!(
+        TabularStrategy::from_json(&strategy.to_json()).unwrap(),
+        strategy
+    );
+    assert!(TabularStrategy::from_bytes(&strategy.to_bytes()[..10]).is_err());
+
+    let mut agent = TabularAgent::new(std::sync::Arc::new(strategy), ChaCha12Rng::seed_from_u64(0));
+    for _ in 0..10 {
+        assert_eq!(agent.choose_call(&game, player_id), DiceCall::Bullshit);
+    }
+}
+
+#[test]
+fn test_strategy_table_rejects_trailing_bytes() {
+    let mut strategy = TabularStrategy::new();
+    strategy.insert(
+        InfoSetKey::new(&[Dice(2)], &[bid(1, 2)]),
+        vec![(DiceCall::Bullshit, 1.0)],
+    );
+    let bytes = strategy.to_bytes();
+    assert_eq!(TabularStrategy::from_bytes(&bytes).unwrap(), strategy);
+    assert!(TabularStrategy::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    assert!(TabularStrategy::from_bytes(&bytes.repeat(2)).is_err());
+}
+
+#[test]
+fn test_player_profiles_from_revealed_round() {
+    let [a, b, c] = three_players();
+    let mut game = scenario(RuleVariant::Standard, &[(2, 3), (3, 3)]);
+    game.apply_call(DiceCall::Bullshit).unwrap().unwrap();
+    let mut profiles = PlayerProfiles::new();
+    profiles.observe_history(game.rules(), game.history());
+
+    // b holds a three and expects half a three among the other three dice
+    let honest = profiles.get(&b).unwrap();
+    assert_eq!(honest.bluff_rate(), Some(0.0));
+    assert_eq!(honest.aggression(), Some(0.5));
+    assert_eq!(honest.challenge_rate(), None);
+
+    let bluffer = profiles.get(&c).unwrap();
+    assert_eq!(bluffer.bluff_rate(), Some(1.0));
+    assert_eq!(bluffer.challenge_rate(), Some(0.0));
+
+    let challenger = profiles.get(&a).unwrap();
+    assert_eq!(challenger.challenge_rate(), Some(1.0));
+    assert_eq!(challenger.challenge_accuracy(), Some(1.0));
+    assert_eq!(challenger.spot_on_rate(), Some(0.0));
+    assert_eq!(challenger.spot_on_accuracy(), None);
+}
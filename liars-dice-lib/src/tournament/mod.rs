@@ -0,0 +1,326 @@
+mod rating;
+
+pub use rating::{TrueSkill, elo_update, wilson_interval};
+
+use crate::agent::Agent;
+use crate::{LiarsDiceGame, LiarsDiceState};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+/// Builds a fresh agent for every game, seeded from the match rng so results are reproducible
+pub type AgentFactory = Box<dyn Fn(ChaCha12Rng) -> Box<dyn Agent>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TournamentFormat {
+    /// Every entrant plays a match against every other entrant
+    RoundRobin,
+    /// Entrants on similar scores are paired up each round, without repeating a pairing where possible
+    Swiss { rounds: usize },
+    /// The first entrant holds the hill and each other entrant challenges whoever holds it in turn
+    KingOfTheHill,
+}
+
+#[derive(Debug, Clone)]
+pub struct TournamentConfig {
+    pub format: TournamentFormat,
+    /// Games in a single match, with seats swapping every game
+    pub games_per_match: usize,
+    pub starting_dice: u8,
+    /// Every match gets its own stream of this seed
+    pub seed: u64,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self {
+            format: TournamentFormat::RoundRobin,
+            games_per_match: 100,
+            starting_dice: 5,
+            seed: 0,
+        }
+    }
+}
+
+struct Entrant {
+    name: String,
+    factory: AgentFactory,
+}
+
+#[derive(Debug, Clone)]
+struct Record {
+    games: usize,
+    wins: usize,
+    /// 1 for a match won, 0.5 for a tied match, used for Swiss pairings
+    match_points: f64,
+    elo: f64,
+    trueskill: TrueSkill,
+}
+
+impl Default for Record {
+    fn default() -> Self {
+        Self {
+            games: 0,
+            wins: 0,
+            match_points: 0.0,
+            elo: rating::ELO_START,
+            trueskill: TrueSkill::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Standing {
+    pub name: String,
+    pub games: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    /// 95% confidence interval of the win rate
+    pub confidence: (f64, f64),
+    pub elo: f64,
+    pub trueskill: TrueSkill,
+}
+
+#[derive(Debug, Clone)]
+pub struct TournamentReport {
+    /// Best first, ordered by Elo
+    pub standings: Vec<Standing>,
+    /// Whoever held the hill at the end of a king of the hill tournament
+    pub king: Option<String>,
+}
+
+pub struct Tournament {
+    config: TournamentConfig,
+    entrants: Vec<Entrant>,
+}
+
+impl Tournament {
+    pub fn new(config: TournamentConfig) -> Self {
+        Self {
+            config,
+            entrants: vec![],
+        }
+    }
+
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn(ChaCha12Rng) -> Box<dyn Agent> + 'static,
+    ) {
+        self.entrants.push(Entrant {
+            name: name.into(),
+            factory: Box::new(factory),
+        });
+    }
+
+    pub fn run(&self) -> TournamentReport {
+        assert!(self.entrants.len() > 1, "Need at least two entrants");
+        let mut records = vec![Record::default(); self.entrants.len()];
+        let mut match_index = 0;
+        let mut king = None;
+        match self.config.format {
+            TournamentFormat::RoundRobin => {
+                for a in 0..self.entrants.len() {
+                    for b in a + 1..self.entrants.len() {
+                        self.play_match(a, b, match_index, &mut records);
+                        match_index += 1;
+                    }
+                }
+            }
+            TournamentFormat::Swiss { rounds } => {
+                let mut played = BTreeSet::new();
+                for _ in 0..rounds {
+                    for (a, b) in swiss_pairings(&records, &played) {
+                        self.play_match(a, b, match_index, &mut records);
+                        played.insert((a.min(b), a.max(b)));
+                        match_index += 1;
+                    }
+                }
+            }
+            TournamentFormat::KingOfTheHill => {
+                let mut holder = 0;
+                for challenger in 1..self.entrants.len() {
+                    let (holder_wins, challenger_wins) =
+                        self.play_match(holder, challenger, match_index, &mut records);
+                    if challenger_wins > holder_wins {
+                        holder = challenger;
+                    }
+                    match_index += 1;
+                }
+                king = Some(self.entrants[holder].name.clone());
+            }
+        }
+        let mut standings: Vec<Standing> = self
+            .entrants
+            .iter()
+            .zip(records)
+            .map(|(entrant, record)| Standing {
+                name: entrant.name.clone(),
+                games: record.games,
+                wins: record.wins,
+                win_rate: match record.games {
+                    0 => 0.0,
+                    games => record.wins as f64 / games as f64,
+                },
+                confidence: wilson_interval(record.wins, record.games),
+                elo: record.elo,
+                trueskill: record.trueskill,
+            })
+            .collect();
+        standings.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+        TournamentReport { standings, king }
+    }
+
+    /// Plays a full match between two entrants, returning how many games each of them won
+    fn play_match(
+        &self,
+        a: usize,
+        b: usize,
+        match_index: u64,
+        records: &mut [Record],
+    ) -> (usize, usize) {
+        let mut rng = ChaCha12Rng::seed_from_u64(self.config.seed);
+        rng.set_stream(match_index);
+        let seats = [Uuid::from_u128(1), Uuid::from_u128(2)];
+        let mut wins = (0, 0);
+        for game_index in 0..self.config.games_per_match {
+            // Swap seats every game to cancel out any seating bias
+            let order = match game_index % 2 {
+                0 => [a, b],
+                _ => [b, a],
+            };
+            let mut agents: BTreeMap<Uuid, Box<dyn Agent>> = seats
+                .iter()
+                .zip(order)
+                .map(|(seat, entrant)| {
+                    let agent_rng = ChaCha12Rng::from_rng(&mut rng);
+                    (*seat, (self.entrants[entrant].factory)(agent_rng))
+                })
+                .collect();
+            let mut game = LiarsDiceGame::new(
+                ChaCha12Rng::from_rng(&mut rng),
+                self.config.starting_dice,
+                &seats,
+            );
+            let winning_seat = match play_game(&mut game, &mut agents) {
+                Ok(winner) => winner,
+                // Illegal calls and failed agents forfeit the game
+                Err((offender, _)) => *seats.iter().find(|seat| **seat != offender).unwrap(),
+            };
+            let (winner, loser) = match winning_seat == seats[0] {
+                true => (order[0], order[1]),
+                false => (order[1], order[0]),
+            };
+            match winner == a {
+                true => wins.0 += 1,
+                false => wins.1 += 1,
+            }
+            record_game(records, winner, loser);
+        }
+        let (a_points, b_points) = match wins.0.cmp(&wins.1) {
+            std::cmp::Ordering::Greater => (1.0, 0.0),
+            std::cmp::Ordering::Less => (0.0, 1.0),
+            std::cmp::Ordering::Equal => (0.5, 0.5),
+        };
+        records[a].match_points += a_points;
+        records[b].match_points += b_points;
+        wins
+    }
+}
+
+/// Plays a game to the end, asking each seat's agent for its calls.
+/// A game that hasn't been initialised yet is started first, and chance nodes are sampled.
+/// An agent that makes an illegal call or fails stops the game, and comes back as the error with the reason.
+pub fn play_game(
+    game: &mut LiarsDiceGame,
+    agents: &mut BTreeMap<Uuid, Box<dyn Agent>>,
+) -> Result<Uuid, (Uuid, &'static str)> {
+    loop {
+        let player_id = match game.get_state() {
+            LiarsDiceState::Start(start) => {
+                start.initialise_game();
+                continue;
+            }
+            LiarsDiceState::Chance(chance) => {
+                chance.sample();
+                continue;
+            }
+            LiarsDiceState::GameComplete(complete) => return Ok(complete.winner_id),
+            LiarsDiceState::PlayerTurn(turn) => turn.player_id,
+        };
+        let agent = agents
+            .get_mut(&player_id)
+            .expect("Every player should have an agent");
+        let call = agent.choose_call(game, player_id);
+        if let Some(reason) = agent.failure() {
+            return Err((player_id, reason));
+        }
+        game.apply_call(call).map_err(|e| (player_id, e))?;
+    }
+}
+
+fn record_game(records: &mut [Record], winner: usize, loser: usize) {
+    let (winner_elo, loser_elo) = elo_update(records[winner].elo, records[loser].elo);
+    let (winner_skill, loser_skill) =
+        TrueSkill::update(records[winner].trueskill, records[loser].trueskill);
+    records[winner].elo = winner_elo;
+    records[winner].trueskill = winner_skill;
+    records[winner].wins += 1;
+    records[winner].games += 1;
+    records[loser].elo = loser_elo;
+    records[loser].trueskill = loser_skill;
+    records[loser].games += 1;
+}
+
+/// Pairs entrants with the closest score they haven't played yet.
+/// With an odd number of entrants the lowest ranked one sits the round out.
+fn swiss_pairings(records: &[Record], played: &BTreeSet<(usize, usize)>) -> Vec<(usize, usize)> {
+    let mut ranked: Vec<usize> = (0..records.len()).collect();
+    ranked.sort_by(|a, b| {
+        records[*b]
+            .match_points
+            .total_cmp(&records[*a].match_points)
+    });
+    let mut pairs = vec![];
+    while ranked.len() > 1 {
+        let a = ranked.remove(0);
+        let opponent = ranked
+            .iter()
+            .position(|b| !played.contains(&(a.min(*b), a.max(*b))))
+            .unwrap_or(0);
+        let b = ranked.remove(opponent);
+        pairs.push((a, b));
+    }
+    pairs
+}
+
+impl Display for TournamentReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>6} {:>6} {:>16} {:>8} {:>16}",
+            "Agent", "Games", "Wins", "Win rate (95%)", "Elo", "TrueSkill"
+        )?;
+        for s in &self.standings {
+            writeln!(
+                f,
+                "{:<20} {:>6} {:>6} {:>5.1}% {:>4.1}-{:<4.1} {:>8.0} {:>7.2} ± {:<5.2}",
+                s.name,
+                s.games,
+                s.wins,
+                s.win_rate * 100.0,
+                s.confidence.0 * 100.0,
+                s.confidence.1 * 100.0,
+                s.elo,
+                s.trueskill.mu,
+                s.trueskill.sigma
+            )?;
+        }
+        if let Some(king) = &self.king {
+            writeln!(f, "King of the hill: {king}")?;
+        }
+        Ok(())
+    }
+}
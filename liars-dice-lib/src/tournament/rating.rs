@@ -0,0 +1,101 @@
+use std::f64::consts::PI;
+
+pub const ELO_START: f64 = 1500.0;
+const ELO_K: f64 = 32.0;
+
+const TRUESKILL_MU: f64 = 25.0;
+const TRUESKILL_SIGMA: f64 = TRUESKILL_MU / 3.0;
+const TRUESKILL_BETA: f64 = TRUESKILL_SIGMA / 2.0;
+const TRUESKILL_TAU: f64 = TRUESKILL_SIGMA / 100.0;
+
+/// Moves both ratings after a single game, returning the new (winner, loser) pair
+pub fn elo_update(winner: f64, loser: f64) -> (f64, f64) {
+    let expected = 1.0 / (1.0 + 10f64.powf((loser - winner) / 400.0));
+    let delta = ELO_K * (1.0 - expected);
+    (winner + delta, loser - delta)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrueSkill {
+    pub mu: f64,
+    pub sigma: f64,
+}
+
+impl Default for TrueSkill {
+    fn default() -> Self {
+        Self {
+            mu: TRUESKILL_MU,
+            sigma: TRUESKILL_SIGMA,
+        }
+    }
+}
+
+impl TrueSkill {
+    /// The skill we are fairly sure the player has at least, used for ranking
+    pub fn conservative(&self) -> f64 {
+        self.mu - 3.0 * self.sigma
+    }
+
+    /// Two player TrueSkill update without draws, returning the new (winner, loser) pair
+    pub fn update(winner: TrueSkill, loser: TrueSkill) -> (TrueSkill, TrueSkill) {
+        let winner_var = winner.sigma.powi(2) + TRUESKILL_TAU.powi(2);
+        let loser_var = loser.sigma.powi(2) + TRUESKILL_TAU.powi(2);
+        let c = (2.0 * TRUESKILL_BETA.powi(2) + winner_var + loser_var).sqrt();
+        let t = (winner.mu - loser.mu) / c;
+        let v = normal_pdf(t) / normal_cdf(t).max(f64::MIN_POSITIVE);
+        let w = v * (v + t);
+        let updated = |rating: TrueSkill, var: f64, sign: f64| TrueSkill {
+            mu: rating.mu + sign * var / c * v,
+            sigma: (var * (1.0 - var / c.powi(2) * w)).max(0.0).sqrt(),
+        };
+        (
+            updated(winner, winner_var, 1.0),
+            updated(loser, loser_var, -1.0),
+        )
+    }
+}
+
+/// 95% Wilson score interval for `wins` out of `games`
+pub fn wilson_interval(wins: usize, games: usize) -> (f64, f64) {
+    if games == 0 {
+        return (0.0, 1.0);
+    }
+    let z = 1.96f64;
+    let n = games as f64;
+    let p = wins as f64 / n;
+    let centre = p + z * z / (2.0 * n);
+    let spread = z * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+    let denominator = 1.0 + z * z / n;
+    (
+        ((centre - spread) / denominator).max(0.0),
+        ((centre + spread) / denominator).min(1.0),
+    )
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / 2f64.sqrt())
+}
+
+/// Complementary error function, accurate to about 1e-7 (Numerical Recipes)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+            .exp();
+    match x >= 0.0 {
+        true => r,
+        false => 2.0 - r,
+    }
+}
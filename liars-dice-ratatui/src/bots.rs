@@ -0,0 +1,133 @@
+use crate::tuirealm_data::UserEvent;
+use liars_dice_lib::LiarsDiceGame;
+use liars_dice_lib::agent::{
+    Agent, IsmctsAgent, IsmctsConfig, NeuralAgent, NeuralNetwork, RandomAgent, Selection,
+    TabularAgent,
+};
+use liars_dice_lib::strategy::TabularStrategy;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::mpsc::{Sender, channel};
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+pub const AGENTS: &str =
+    "random, ismcts:<iterations>, neural:<weights.safetensors>, tabular:<strategy>";
+
+pub type BoxedAgent = Box<dyn Agent + Send>;
+
+/// Someone at the table, played from the keyboard unless they have an agent
+pub struct Seat {
+    pub name: String,
+    pub agent: Option<BoxedAgent>,
+}
+
+impl Seat {
+    pub fn human(name: String) -> Self {
+        Self { name, agent: None }
+    }
+
+    pub fn bot(name: String, agent: BoxedAgent) -> Self {
+        Self {
+            name,
+            agent: Some(agent),
+        }
+    }
+}
+
+/// Builds an agent from the names the tournament runner uses, such as `ismcts:500`
+pub fn parse_agent(
+    spec: &str,
+    players: usize,
+    starting_dice: u8,
+    mut rng: ChaCha12Rng,
+) -> Result<BoxedAgent, String> {
+    match spec.split_once(':') {
+        None if spec == "random" => Ok(Box::new(RandomAgent::new(rng))),
+        Some(("ismcts", iterations)) => {
+            let config = IsmctsConfig {
+                iterations: iterations
+                    .parse()
+                    .map_err(|_| format!("Could not read {iterations} as iterations"))?,
+                ..IsmctsConfig::default()
+            };
+            let rollout = RandomAgent::new(ChaCha12Rng::from_rng(&mut rng));
+            Ok(Box::new(IsmctsAgent::new(config, rng, rollout)))
+        }
+        Some(("neural", path)) => {
+            let network =
+                NeuralNetwork::load(path).map_err(|e| format!("Could not load {path}: {e}"))?;
+            let encoder = network.encoder();
+            if encoder.players != players || encoder.starting_dice != starting_dice {
+                return Err(format!(
+                    "{path} was trained for {} players with {} dice, not {players} with {starting_dice}",
+                    encoder.players, encoder.starting_dice
+                ));
+            }
+            Ok(Box::new(NeuralAgent::new(network, Selection::Sample, rng)))
+        }
+        Some(("tabular", path)) => {
+            let strategy =
+                TabularStrategy::load(path).map_err(|e| format!("Could not load {path}: {e}"))?;
+            Ok(Box::new(TabularAgent::new(Arc::new(strategy), rng)))
+        }
+        _ => Err(format!("Unknown agent {spec}, expected one of {AGENTS}")),
+    }
+}
+
+struct Request {
+    turn: usize,
+    game: LiarsDiceGame,
+    player_id: Uuid,
+}
+
+/// Runs the bots on their own thread, so searching doesn't hold up the interface.
+/// Each call comes back as a [`UserEvent::BotCalled`], no sooner than `delay` after it was asked for,
+/// so people can follow what the bots are doing.
+pub struct BotWorker {
+    requests: Sender<Request>,
+}
+
+impl BotWorker {
+    pub fn spawn(
+        mut agents: BTreeMap<Uuid, BoxedAgent>,
+        events: Sender<UserEvent>,
+        delay: Duration,
+    ) -> Self {
+        let (requests, receiver) = channel::<Request>();
+        thread::spawn(move || {
+            for request in receiver {
+                let started = Instant::now();
+                let agent = agents
+                    .get_mut(&request.player_id)
+                    .expect("Only bots should be asked for calls");
+                let call = agent.choose_call(&request.game, request.player_id);
+                thread::sleep(delay.saturating_sub(started.elapsed()));
+                let event = UserEvent::BotCalled {
+                    turn: request.turn,
+                    player_id: request.player_id,
+                    call,
+                };
+                if events.send(event).is_err() {
+                    return;
+                }
+            }
+        });
+        Self { requests }
+    }
+
+    /// Asks `player_id`'s agent for a call, tagged with `turn` so stale answers can be told apart
+    pub fn request(&self, turn: usize, game: &LiarsDiceGame, player_id: Uuid) {
+        let request = Request {
+            turn,
+            game: game.clone(),
+            player_id,
+        };
+        self.requests
+            .send(request)
+            .expect("Bot thread should outlive the interface");
+    }
+}
@@ -0,0 +1,169 @@
+use crate::components::choice_panel::describe;
+use crate::tuirealm_data::{Msg, UserEvent};
+use liars_dice_lib::{DiceCall, GameEvent, LiarsDiceGame};
+use ratatui::Frame;
+use ratatui::layout::{Margin, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{
+    Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+};
+use std::collections::BTreeMap;
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+use uuid::Uuid;
+
+/// One line of the bid history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryEntry {
+    /// Numbered from one
+    Round(usize),
+    Call {
+        name: String,
+        call: DiceCall,
+    },
+    /// The round's challenge was settled, one way or the other
+    Settled {
+        correct: bool,
+    },
+}
+
+impl HistoryEntry {
+    /// Every call since the game started, round by round
+    pub fn from_game(game: &LiarsDiceGame, names: &BTreeMap<Uuid, String>) -> Vec<Self> {
+        let mut round = 0;
+        game.history()
+            .iter()
+            .map(|event| match event {
+                GameEvent::RoundStarted { .. } => {
+                    round += 1;
+                    HistoryEntry::Round(round)
+                }
+                GameEvent::Called { player_id, call } => HistoryEntry::Call {
+                    name: names
+                        .get(player_id)
+                        .cloned()
+                        .unwrap_or_else(|| player_id.to_string()),
+                    call: *call,
+                },
+                GameEvent::Revealed(outcome) => HistoryEntry::Settled {
+                    correct: outcome.correct_call,
+                },
+            })
+            .collect()
+    }
+
+    fn line(&self) -> Line<'static> {
+        match self {
+            HistoryEntry::Round(round) => Line::styled(
+                format!("Round {round}"),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            HistoryEntry::Call { name, call } => Line::raw(format!("  {name}: {}", describe(call))),
+            HistoryEntry::Settled { correct } => {
+                let verdict = match correct {
+                    true => "  the challenge was right",
+                    false => "  the challenge was wrong",
+                };
+                Line::styled(verdict, Style::default().fg(Color::DarkGray))
+            }
+        }
+    }
+}
+
+/// Every call made so far, following the latest until PageUp scrolls back.
+/// PageDown scrolls forward again, and End goes back to following.
+#[derive(Default)]
+pub struct BidHistory {
+    props: Props,
+    entries: Vec<HistoryEntry>,
+    /// The first line showing, or `None` to keep the latest call in view
+    offset: Option<usize>,
+    /// Lines that fit in the panel when it was last drawn
+    height: usize,
+}
+
+impl BidHistory {
+    fn bottom(&self) -> usize {
+        self.entries.len().saturating_sub(self.height)
+    }
+
+    fn scroll(&mut self, lines: isize) {
+        let from = self.offset.unwrap_or(self.bottom()) as isize;
+        let to = (from + lines).clamp(0, self.bottom() as isize) as usize;
+        self.offset = match to == self.bottom() {
+            true => None,
+            false => Some(to),
+        };
+    }
+}
+
+impl MockComponent for BidHistory {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.height = area.height.saturating_sub(2) as usize;
+        let offset = self.offset.unwrap_or(self.bottom()).min(self.bottom());
+        let lines: Vec<Line> = self.entries.iter().map(HistoryEntry::line).collect();
+        let paragraph = Paragraph::new(lines)
+            .scroll((offset as u16, 0))
+            .block(Block::default().borders(Borders::ALL).title("Bids"));
+        frame.render_widget(paragraph, area);
+        let mut scrollbar = ScrollbarState::new(self.bottom()).position(offset);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar,
+        );
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        let page = self.height.max(1) as isize;
+        match cmd {
+            Cmd::Scroll(Direction::Up) => self.scroll(-page),
+            Cmd::Scroll(Direction::Down) => self.scroll(page),
+            Cmd::GoTo(Position::End) => self.offset = None,
+            _ => return CmdResult::None,
+        }
+        CmdResult::Changed(State::None)
+    }
+}
+
+impl Component<Msg, UserEvent> for BidHistory {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd = match ev {
+            Event::User(UserEvent::HistoryChanged(entries)) => {
+                self.entries = entries;
+                return Some(Msg::Redraw);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => Cmd::Scroll(Direction::Up),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => Cmd::Scroll(Direction::Down),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => Cmd::GoTo(Position::End),
+            _ => return None,
+        };
+        match self.perform(cmd) {
+            CmdResult::Changed(_) => Some(Msg::Redraw),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::tuirealm_data::{Msg, UserEvent};
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+
+/// Takes the place of the hand and choices while a bot picks its call, and passes the call on once it arrives
+pub struct BotStatus {
+    props: Props,
+    name: String,
+}
+
+impl BotStatus {
+    pub fn new(name: String) -> Self {
+        Self {
+            props: Props::default(),
+            name,
+        }
+    }
+}
+
+impl MockComponent for BotStatus {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let paragraph = Paragraph::new(format!("{} is thinking…", self.name))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _cmd: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, UserEvent> for BotStatus {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        match ev {
+            Event::User(UserEvent::BotCalled {
+                turn,
+                player_id,
+                call,
+            }) => Some(Msg::BotCalled {
+                turn,
+                player_id,
+                call,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -1,35 +1,263 @@
+use crate::components::die_glyph;
 use crate::tuirealm_data::{Msg, UserEvent};
-use ratatui::layout::Rect;
+use liars_dice_lib::encoding::encode_call;
+use liars_dice_lib::{Dice, DiceCall};
 use ratatui::Frame;
-use tuirealm::command::{Cmd, CmdResult};
-use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use tuirealm::command::{Cmd, CmdResult, Direction};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State, StateValue};
+
+/// Why the engine refused the last call, shown under the selectors. An empty string clears it.
+pub const ERROR: Attribute = Attribute::Custom("error");
+/// Whether the proposed call is waiting for the player to confirm it
+pub const CONFIRMING: Attribute = Attribute::Custom("confirming");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Field {
+    Count,
+    Face,
+    Bullshit,
+    SpotOn,
+}
 
-pub struct ChoicePanel {}
+const FIELDS: [Field; 4] = [Field::Count, Field::Face, Field::Bullshit, Field::SpotOn];
+
+/// Lets the current player pick a raise, Bullshit or SpotOn.
+/// Enter proposes the call, and a second Enter confirms it once the engine has accepted it.
+pub struct ChoicePanel {
+    props: Props,
+    legal: Vec<DiceCall>,
+    /// No bid can be higher than the number of dice in play
+    max_count: usize,
+    count: usize,
+    face: u8,
+    focus: Field,
+    /// Whether the last key was a digit typed into the count, so the next one adds on to it
+    typing: bool,
+}
+
+impl ChoicePanel {
+    /// Starts on the lowest raise the player can make
+    pub fn new(legal: Vec<DiceCall>, max_count: usize) -> Self {
+        let (count, face) = legal
+            .iter()
+            .find_map(|call| match call {
+                DiceCall::Increase { count, value } => Some((*count, value.value())),
+                _ => None,
+            })
+            .unwrap_or((1, 1));
+        Self {
+            props: Props::default(),
+            legal,
+            max_count: max_count.max(1),
+            count,
+            face,
+            focus: Field::Count,
+            typing: false,
+        }
+    }
+
+    fn raise(&self) -> DiceCall {
+        DiceCall::Increase {
+            count: self.count,
+            value: Dice::new(self.face).unwrap(),
+        }
+    }
+
+    fn selected(&self) -> DiceCall {
+        match self.focus {
+            Field::Bullshit => DiceCall::Bullshit,
+            Field::SpotOn => DiceCall::SpotOn,
+            Field::Count | Field::Face => self.raise(),
+        }
+    }
+
+    /// Whether the call a field stands for can be made, as calls that can't are greyed out
+    pub(crate) fn legal(&self, field: Field) -> bool {
+        match field {
+            Field::Bullshit => self.legal.contains(&DiceCall::Bullshit),
+            Field::SpotOn => self.legal.contains(&DiceCall::SpotOn),
+            Field::Count | Field::Face => self.legal.contains(&self.raise()),
+        }
+    }
+
+    fn confirming(&self) -> bool {
+        matches!(self.props.get(CONFIRMING), Some(AttrValue::Flag(true)))
+    }
+
+    fn error(&self) -> Option<String> {
+        match self.props.get(ERROR) {
+            Some(AttrValue::String(error)) if !error.is_empty() => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Any change to the selection makes the last error and confirmation stale
+    fn clear_prompt(&mut self) {
+        self.props.set(ERROR, AttrValue::String(String::new()));
+        self.props.set(CONFIRMING, AttrValue::Flag(false));
+    }
+
+    fn move_focus(&mut self, step: isize) {
+        let index = FIELDS
+            .iter()
+            .position(|field| *field == self.focus)
+            .unwrap() as isize;
+        self.focus = FIELDS[(index + step).rem_euclid(FIELDS.len() as isize) as usize];
+    }
+
+    fn adjust(&mut self, step: isize) {
+        match self.focus {
+            Field::Count => {
+                self.count = (self.count as isize + step).clamp(1, self.max_count as isize) as usize
+            }
+            Field::Face => self.face = (self.face as isize + step).clamp(1, 6) as u8,
+            _ => (),
+        }
+    }
+
+    /// Digits pick the face directly, or build up the count.
+    /// The first digit replaces the count, and the ones typed straight after it add on.
+    fn type_digit(&mut self, digit: usize, typing: bool) {
+        match self.focus {
+            Field::Count => {
+                let typed = self.count * 10 + digit;
+                self.count = match typing && (1..=self.max_count).contains(&typed) {
+                    true => typed,
+                    false => digit.clamp(1, self.max_count),
+                };
+                self.typing = true;
+            }
+            Field::Face if (1..=6).contains(&digit) => self.face = digit as u8,
+            _ => (),
+        }
+    }
+
+    fn style(&self, field: Field) -> Style {
+        let style = match self.legal(field) {
+            true => Style::default(),
+            false => Style::default().fg(Color::DarkGray),
+        };
+        match field == self.focus {
+            true => style.add_modifier(Modifier::REVERSED),
+            false => style,
+        }
+    }
+}
 
 impl MockComponent for ChoicePanel {
     fn view(&mut self, frame: &mut Frame, area: Rect) {
-        todo!()
+        let selectors = Line::from(vec![
+            Span::raw("Count "),
+            Span::styled(format!("< {} >", self.count), self.style(Field::Count)),
+            Span::raw("   Face "),
+            Span::styled(
+                format!("< {} {} >", die_glyph(self.face), self.face),
+                self.style(Field::Face),
+            ),
+            Span::raw("   "),
+            Span::styled("[ Bullshit ]", self.style(Field::Bullshit)),
+            Span::raw(" "),
+            Span::styled("[ Spot on ]", self.style(Field::SpotOn)),
+        ]);
+        let prompt = match (self.error(), self.confirming()) {
+            (Some(error), _) => Line::styled(error, Style::default().fg(Color::Red)),
+            (None, true) => Line::styled(
+                format!(
+                    "Call {}? Enter to confirm, Esc to change it",
+                    describe(&self.selected())
+                ),
+                Style::default().fg(Color::Yellow),
+            ),
+            (None, false) => Line::styled(
+                "←/→ move, ↑/↓ or digits change, Enter to call, ? for more keys",
+                Style::default().fg(Color::DarkGray),
+            ),
+        };
+        let block = Block::default().borders(Borders::ALL).title("Your call");
+        frame.render_widget(Paragraph::new(vec![selectors, prompt]).block(block), area);
     }
 
     fn query(&self, attr: Attribute) -> Option<AttrValue> {
-        todo!()
+        self.props.get(attr)
     }
 
     fn attr(&mut self, attr: Attribute, value: AttrValue) {
-        todo!()
+        self.props.set(attr, value)
     }
 
+    /// The selected call, as its action id
     fn state(&self) -> State {
-        todo!()
+        State::One(StateValue::Usize(encode_call(&self.selected())))
     }
 
     fn perform(&mut self, cmd: Cmd) -> CmdResult {
-        todo!()
+        // Only digits typed one after another make up a count
+        let typing = std::mem::take(&mut self.typing);
+        match cmd {
+            Cmd::Move(Direction::Left) => self.move_focus(-1),
+            Cmd::Move(Direction::Right) => self.move_focus(1),
+            Cmd::Move(Direction::Up) => self.adjust(1),
+            Cmd::Move(Direction::Down) => self.adjust(-1),
+            Cmd::Type(c) => match c.to_digit(10) {
+                Some(digit) => self.type_digit(digit as usize, typing),
+                None => return CmdResult::None,
+            },
+            Cmd::Submit => return CmdResult::Submit(self.state()),
+            Cmd::Cancel => (),
+            _ => return CmdResult::None,
+        }
+        self.clear_prompt();
+        CmdResult::Changed(self.state())
     }
 }
 
 impl Component<Msg, UserEvent> for ChoicePanel {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
-        todo!()
+        let cmd = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left | Key::BackTab,
+                ..
+            }) => Cmd::Move(Direction::Left),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right | Key::Tab,
+                ..
+            }) => Cmd::Move(Direction::Right),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => Cmd::Move(Direction::Up),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => Cmd::Move(Direction::Down),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(c), ..
+            }) => Cmd::Type(c),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Cmd::Submit,
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) if self.confirming() => {
+                self.perform(Cmd::Cancel);
+                return Some(Msg::CancelCall);
+            }
+            _ => return None,
+        };
+        match self.perform(cmd) {
+            CmdResult::Submit(_) if self.confirming() => Some(Msg::ConfirmCall),
+            CmdResult::Submit(_) => Some(Msg::ProposeCall(self.selected())),
+            CmdResult::Changed(_) => Some(Msg::Redraw),
+            _ => None,
+        }
+    }
+}
+
+/// How a call reads in prompts, such as `3 × 4` or `Bullshit`
+pub fn describe(call: &DiceCall) -> String {
+    match call {
+        DiceCall::Bullshit => "Bullshit".to_string(),
+        DiceCall::SpotOn => "Spot on".to_string(),
+        DiceCall::Increase { count, value } => format!("{count} × {}", value.value()),
     }
 }
@@ -0,0 +1,63 @@
+use crate::components::die_glyph;
+use crate::tuirealm_data::{Msg, UserEvent};
+use liars_dice_lib::Dice;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::widgets::{Block, Borders, Paragraph};
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+
+/// The dice only the current player should see
+pub struct Hand {
+    props: Props,
+    name: String,
+    dice: Vec<Dice>,
+}
+
+impl Hand {
+    pub fn new(name: String, mut dice: Vec<Dice>) -> Self {
+        dice.sort();
+        Self {
+            props: Props::default(),
+            name,
+            dice,
+        }
+    }
+}
+
+impl MockComponent for Hand {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let dice: Vec<String> = self
+            .dice
+            .iter()
+            .map(|die| format!("{} {}", die_glyph(die.value()), die.value()))
+            .collect();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{}'s dice", self.name));
+        frame.render_widget(Paragraph::new(dice.join("   ")).block(block), area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _cmd: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, UserEvent> for Hand {
+    fn on(&mut self, _ev: Event<UserEvent>) -> Option<Msg> {
+        None
+    }
+}
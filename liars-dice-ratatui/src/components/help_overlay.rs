@@ -0,0 +1,81 @@
+use crate::keymap::{Action, Keymap};
+use crate::tuirealm_data::{Msg, UserEvent};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+
+/// Turns the keys from the keymap into actions whichever component has focus,
+/// and lists them when drawn
+pub struct HelpOverlay {
+    props: Props,
+    keymap: Keymap,
+}
+
+impl HelpOverlay {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            props: Props::default(),
+            keymap,
+        }
+    }
+
+    /// Room for a line per action and the borders
+    pub fn height() -> u16 {
+        Action::ALL.len() as u16 + 2
+    }
+}
+
+impl MockComponent for HelpOverlay {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<_> = Action::ALL
+            .into_iter()
+            .map(|action| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<12}", self.keymap.keys(action).join(", ")),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(action.description()),
+                ])
+            })
+            .collect();
+        let block = Block::default().borders(Borders::ALL).title("Keys");
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _cmd: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, UserEvent> for HelpOverlay {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let Event::Keyboard(key) = ev else {
+            return None;
+        };
+        match self.keymap.action(&key)? {
+            Action::Quit => Some(Msg::AppClose),
+            Action::Help => Some(Msg::ToggleHelp),
+            Action::NewGame => Some(Msg::NewGame),
+            Action::ToggleHints => Some(Msg::ToggleHints),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::components::choice_panel::describe;
+use crate::hints::Hint;
+use crate::tuirealm_data::{Msg, UserEvent};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+
+/// Odds for the player whose turn it is, drawn over the table.
+/// The keymap decides which key shows it.
+#[derive(Default)]
+pub struct HintOverlay {
+    props: Props,
+    hint: Option<Hint>,
+}
+
+impl HintOverlay {
+    pub fn new(hint: Hint) -> Self {
+        Self {
+            props: Props::default(),
+            hint: Some(hint),
+        }
+    }
+}
+
+fn percent(chance: f64) -> String {
+    format!("{:.0}%", chance * 100.0)
+}
+
+fn ev(ev: f64) -> String {
+    format!("{ev:+.2} dice")
+}
+
+impl MockComponent for HintOverlay {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(hint) = &self.hint else {
+            return;
+        };
+        let mut lines = vec![];
+        match hint.bid {
+            Some((bid, holds, exact)) => {
+                lines.push(Line::raw(format!(
+                    "{} holds: {}, exactly: {}",
+                    describe(&bid),
+                    percent(holds),
+                    percent(exact)
+                )));
+                lines.push(Line::raw(format!("Bullshit: {}", ev(hint.bullshit_ev))));
+                lines.push(Line::raw(format!("Spot on: {}", ev(hint.spot_on_ev))));
+            }
+            None => lines.push(Line::raw("No bid yet")),
+        }
+        if let Some((raise, holds)) = hint.best_raise {
+            lines.push(Line::raw(format!(
+                "Safest raise: {} ({})",
+                describe(&raise),
+                percent(holds)
+            )));
+        }
+        if let Some(suggestion) = hint.suggestion {
+            lines.push(Line::styled(
+                format!("Try: {}", describe(&suggestion)),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        let block = Block::default().borders(Borders::ALL).title("Hints");
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _cmd: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, UserEvent> for HintOverlay {
+    fn on(&mut self, _ev: Event<UserEvent>) -> Option<Msg> {
+        None
+    }
+}
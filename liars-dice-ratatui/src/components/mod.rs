@@ -1,2 +1,19 @@
+pub mod bid_history;
+pub mod bot_status;
 pub mod choice_panel;
+pub mod hand;
+pub mod help_overlay;
+pub mod hint_overlay;
+pub mod pass_screen;
 pub mod player_table;
+pub mod replay_viewer;
+pub mod reveal;
+pub mod setup_form;
+
+/// The unicode die showing `face`
+pub fn die_glyph(face: u8) -> char {
+    match face {
+        1..=6 => char::from_u32(0x2680 + face as u32 - 1).unwrap(),
+        _ => '?',
+    }
+}
@@ -0,0 +1,73 @@
+use crate::tuirealm_data::{Msg, UserEvent};
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+
+/// Stands in for the hand and the choice panel between turns,
+/// so the next player can take the keyboard without seeing the last player's dice
+pub struct PassScreen {
+    props: Props,
+    headline: String,
+}
+
+impl PassScreen {
+    pub fn new(headline: String) -> Self {
+        Self {
+            props: Props::default(),
+            headline,
+        }
+    }
+}
+
+impl MockComponent for PassScreen {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let headline = Line::styled(
+            self.headline.as_str(),
+            Style::default().add_modifier(Modifier::BOLD),
+        );
+        let paragraph = Paragraph::new(headline)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        match cmd {
+            Cmd::Submit => CmdResult::Submit(State::None),
+            _ => CmdResult::None,
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for PassScreen {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.perform(Cmd::Submit) {
+                CmdResult::Submit(_) => Some(Msg::HandedOver),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
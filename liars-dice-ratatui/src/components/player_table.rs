@@ -1,33 +1,108 @@
+use crate::components::choice_panel::describe;
 use crate::tuirealm_data::{Msg, UserEvent};
-use ratatui::layout::Rect;
+use liars_dice_lib::{DiceCall, LiarsDiceGame};
 use ratatui::Frame;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::Color;
+use std::collections::BTreeMap;
 use tui_realm_stdlib::Table as TableComponent;
 use tuirealm::command::{Cmd, CmdResult};
-use tuirealm::props::{TableBuilder, TextSpan};
+use tuirealm::props::{Table, TableBuilder, TextSpan};
 use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+use uuid::Uuid;
+
+/// What the table shows for one seat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatRow {
+    pub name: String,
+    pub dice: usize,
+    pub last_call: Option<DiceCall>,
+    pub current: bool,
+}
+
+impl SeatRow {
+    /// A row for every seat, knocked out players included, in seating order
+    pub fn from_game(game: &LiarsDiceGame, names: &BTreeMap<Uuid, String>) -> Vec<Self> {
+        let calls: BTreeMap<Uuid, Option<DiceCall>> = match game.current_player() {
+            Some(_) => game
+                .previous_calls()
+                .into_iter()
+                .map(|(id, _, call)| (id, call))
+                .collect(),
+            None => BTreeMap::new(),
+        };
+        game.player_dices()
+            .into_iter()
+            .map(|(id, dice)| SeatRow {
+                name: names.get(&id).cloned().unwrap_or_else(|| id.to_string()),
+                dice: dice.len(),
+                last_call: calls.get(&id).copied().flatten(),
+                current: game.current_player() == Some(id) && !game.is_complete(),
+            })
+            .collect()
+    }
+}
 
 pub struct PlayerTableComponent {
     component: TableComponent,
 }
 
+impl PlayerTableComponent {
+    pub fn new(rows: &[SeatRow]) -> Self {
+        let mut component = TableComponent::default()
+            .title("Players", Alignment::Left)
+            .headers(["Player", "Dice", "Call"]);
+        component.attr(Attribute::Content, AttrValue::Table(Self::content(rows)));
+        PlayerTableComponent { component }
+    }
+
+    /// The current player is marked and shown in yellow, and knocked out players are greyed out
+    fn content(rows: &[SeatRow]) -> Table {
+        let mut table = TableBuilder::default();
+        for (i, row) in rows.iter().enumerate() {
+            if i > 0 {
+                table.add_row();
+            }
+            let marker = match row.current {
+                true => "▶ ",
+                false => "  ",
+            };
+            let (dice, call) = match (row.dice, row.last_call) {
+                (0, _) => ("out".to_string(), String::new()),
+                (dice, Some(call)) => (dice.to_string(), describe(&call)),
+                (dice, None) => (dice.to_string(), "-".to_string()),
+            };
+            let colour = match (row.dice, row.current) {
+                (0, _) => Color::DarkGray,
+                (_, true) => Color::Yellow,
+                _ => Color::Reset,
+            };
+            let span = |text: String| match row.dice {
+                0 => TextSpan::new(text).fg(colour).strikethrough(),
+                _ => TextSpan::new(text).fg(colour),
+            };
+            table
+                .add_col(span(format!("{marker}{}", row.name)))
+                .add_col(span(dice))
+                .add_col(span(call));
+        }
+        table.build()
+    }
+}
+
 impl Default for PlayerTableComponent {
     fn default() -> Self {
-        let mut component = TableComponent::default();
-        let mut table = TableBuilder::default()
-            .add_col(TextSpan::new("Player"))
-            .add_col(TextSpan::new("Dice"))
-            .add_col(TextSpan::new("Call"))
-            .build();
-        table.push(vec!["Player 1".into(), "5".into(), "None".into()]);
-        table.push(vec!["Player 2".into(), "3".into(), "4 5".into()]);
-        component.attr(Attribute::Content, AttrValue::Table(table));
-        PlayerTableComponent { component }
+        Self::new(&[])
     }
 }
 
 impl Component<Msg, UserEvent> for PlayerTableComponent {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         match ev {
+            Event::User(UserEvent::SeatsChanged(rows)) => {
+                self.attr(Attribute::Content, AttrValue::Table(Self::content(&rows)));
+                Some(Msg::Redraw)
+            }
             _ => None,
         }
     }
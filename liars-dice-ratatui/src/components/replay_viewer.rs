@@ -0,0 +1,228 @@
+use crate::components::choice_panel::describe;
+use crate::components::die_glyph;
+use crate::tuirealm_data::{Msg, UserEvent};
+use liars_dice_lib::record::GameRecord;
+use liars_dice_lib::{Dice, DiceCall, GameEvent, LiarsDiceGame, RuleVariant};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use std::collections::BTreeMap;
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+use uuid::Uuid;
+
+/// The key that shows and hides the dice nobody at the table could see
+pub const DICE_KEY: char = 'd';
+
+/// Steps through a recorded game one event at a time
+pub struct ReplayViewer {
+    props: Props,
+    names: BTreeMap<Uuid, String>,
+    rules: RuleVariant,
+    events: Vec<GameEvent>,
+    /// The record's comment on each event, for calls that had one
+    comments: Vec<Option<String>>,
+    winner: Option<String>,
+    /// How many events have happened so far, always at least the first roll
+    step: usize,
+    show_dice: bool,
+}
+
+impl ReplayViewer {
+    /// `game` is what [`GameRecord::replay`] made of `record`
+    pub fn new(record: &GameRecord, game: &LiarsDiceGame, show_dice: bool) -> Self {
+        let names = record
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (Uuid::from_u128(i as u128 + 1), name.clone()))
+            .collect();
+        let mut calls = record.rounds.iter().flat_map(|round| &round.calls);
+        let comments = game
+            .history()
+            .iter()
+            .map(|event| match event {
+                GameEvent::Called { .. } => calls.next().and_then(|call| call.comment.clone()),
+                _ => None,
+            })
+            .collect();
+        Self {
+            props: Props::default(),
+            names,
+            rules: record.rules,
+            events: game.history().to_vec(),
+            comments,
+            winner: record.winner.clone(),
+            step: 1.min(game.history().len()),
+            show_dice,
+        }
+    }
+
+    fn name(&self, player_id: &Uuid) -> String {
+        self.names
+            .get(player_id)
+            .cloned()
+            .unwrap_or_else(|| player_id.to_string())
+    }
+
+    fn dice(&self, dice: &[Dice], shown: bool) -> Vec<Span<'static>> {
+        dice.iter()
+            .map(|die| match shown {
+                true => Span::raw(format!("{} ", die_glyph(die.value()))),
+                false => Span::styled("? ", Style::default().fg(Color::DarkGray)),
+            })
+            .collect()
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let seen = &self.events[..self.step];
+        let round_start = seen
+            .iter()
+            .rposition(|event| matches!(event, GameEvent::RoundStarted { .. }))
+            .unwrap_or(0);
+        let round = seen
+            .iter()
+            .filter(|event| matches!(event, GameEvent::RoundStarted { .. }))
+            .count();
+        let mut lines = vec![Line::styled(
+            format!(
+                "Round {round}, event {} of {}",
+                self.step,
+                self.events.len()
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        let revealed = matches!(seen.last(), Some(GameEvent::Revealed(_)));
+        if let Some(GameEvent::RoundStarted { player_dices }) = seen.get(round_start) {
+            for (id, dice) in player_dices {
+                let mut spans = vec![Span::raw(format!("{:<20}", self.name(id)))];
+                spans.extend(self.dice(dice, self.show_dice || revealed));
+                lines.push(Line::from(spans));
+            }
+        }
+        lines.push(Line::raw(""));
+        let mut bid = None;
+        for (index, event) in seen.iter().enumerate().skip(round_start + 1) {
+            let style = match index + 1 == self.step {
+                true => Style::default().add_modifier(Modifier::REVERSED),
+                false => Style::default(),
+            };
+            match event {
+                GameEvent::Called { player_id, call } => {
+                    if let DiceCall::Increase { count, value } = call {
+                        bid = Some((*count, *value));
+                    }
+                    let mut spans = vec![Span::styled(
+                        format!("{}: {}", self.name(player_id), describe(call)),
+                        style,
+                    )];
+                    if let Some(comment) = &self.comments[index] {
+                        spans.push(Span::styled(
+                            format!("  {{{comment}}}"),
+                            Style::default().fg(Color::Cyan),
+                        ));
+                    }
+                    lines.push(Line::from(spans));
+                }
+                GameEvent::Revealed(outcome) => {
+                    if let Some((count, face)) = bid {
+                        let matching = self.rules.count_matching(&outcome.tally, face);
+                        lines.push(Line::styled(
+                            format!(
+                                "There were {matching} × {}, against a bid of {count}",
+                                face.value()
+                            ),
+                            style,
+                        ));
+                    }
+                    let verdict = match outcome.correct_call {
+                        true => "The challenge was right",
+                        false => "The challenge was wrong",
+                    };
+                    lines.push(Line::styled(verdict, style));
+                }
+                GameEvent::RoundStarted { .. } => (),
+            }
+        }
+        if self.step == self.events.len()
+            && let Some(winner) = &self.winner
+        {
+            lines.push(Line::styled(
+                format!("{winner} wins"),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        lines
+    }
+}
+
+impl MockComponent for ReplayViewer {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let mut lines = self.lines();
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!("←/→ step, Home/End jump, {DICE_KEY} shows or hides the dice, ? for more keys"),
+            Style::default().fg(Color::DarkGray),
+        ));
+        let block = Block::default().borders(Borders::ALL).title("Replay");
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        let first = 1.min(self.events.len());
+        match cmd {
+            Cmd::Move(Direction::Left) => self.step = self.step.saturating_sub(1).max(first),
+            Cmd::Move(Direction::Right) => self.step = (self.step + 1).min(self.events.len()),
+            Cmd::GoTo(Position::Begin) => self.step = first,
+            Cmd::GoTo(Position::End) => self.step = self.events.len(),
+            Cmd::Toggle => self.show_dice = !self.show_dice,
+            _ => return CmdResult::None,
+        }
+        CmdResult::Changed(State::None)
+    }
+}
+
+impl Component<Msg, UserEvent> for ReplayViewer {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => Cmd::Move(Direction::Left),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => Cmd::Move(Direction::Right),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => Cmd::GoTo(Position::Begin),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => Cmd::GoTo(Position::End),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(DICE_KEY),
+                ..
+            }) => Cmd::Toggle,
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => return Some(Msg::AppClose),
+            _ => return None,
+        };
+        match self.perform(cmd) {
+            CmdResult::Changed(_) => Some(Msg::Redraw),
+            _ => None,
+        }
+    }
+}
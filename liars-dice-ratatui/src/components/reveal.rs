@@ -0,0 +1,149 @@
+use crate::components::die_glyph;
+use crate::tuirealm_data::{Msg, UserEvent};
+use liars_dice_lib::{Dice, RuleVariant};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+
+/// Shows everyone's dice after a challenge, one player per tick, then the tally against the bid and what came of it
+pub struct Reveal {
+    props: Props,
+    /// Each player still in the round, with the dice they held
+    hands: Vec<(String, Vec<Dice>)>,
+    bid_count: usize,
+    bid_face: Dice,
+    rules: RuleVariant,
+    /// Who challenged and who lost dice
+    news: Vec<String>,
+    /// How many hands are showing so far
+    shown: usize,
+}
+
+impl Reveal {
+    pub fn new(
+        hands: Vec<(String, Vec<Dice>)>,
+        bid_count: usize,
+        bid_face: Dice,
+        rules: RuleVariant,
+        news: Vec<String>,
+    ) -> Self {
+        Self {
+            props: Props::default(),
+            hands,
+            bid_count,
+            bid_face,
+            rules,
+            news,
+            shown: 0,
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.shown > self.hands.len()
+    }
+
+    /// Exact matches in green, wild ones in yellow, the rest dimmed
+    fn die(&self, die: Dice) -> Span<'static> {
+        let style = match (die == self.bid_face, self.rules.matches(die, self.bid_face)) {
+            (true, _) => Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+            (false, true) => Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            (false, false) => Style::default().fg(Color::DarkGray),
+        };
+        Span::styled(format!("{} ", die_glyph(die.value())), style)
+    }
+}
+
+impl MockComponent for Reveal {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![Line::from(vec![
+            Span::raw("The bid was "),
+            Span::styled(
+                format!("{} × {}", self.bid_count, die_glyph(self.bid_face.value())),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ])];
+        for (name, dice) in self.hands.iter().take(self.shown) {
+            let mut spans = vec![Span::raw(format!("{name:<20}"))];
+            spans.extend(dice.iter().map(|die| self.die(*die)));
+            lines.push(Line::from(spans));
+        }
+        if self.finished() {
+            let count = self
+                .hands
+                .iter()
+                .flat_map(|(_, dice)| dice)
+                .filter(|die| self.rules.matches(**die, self.bid_face))
+                .count();
+            let colour = match count >= self.bid_count {
+                true => Color::Green,
+                false => Color::Red,
+            };
+            lines.push(Line::styled(
+                format!("There were {count}, against a bid of {}", self.bid_count),
+                Style::default().fg(colour),
+            ));
+            lines.extend(self.news.iter().map(|news| Line::raw(news.clone())));
+            lines.push(Line::styled(
+                "Press Enter to carry on",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        let block = Block::default().borders(Borders::ALL).title("Reveal");
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    /// `Cmd::Tick` shows the next hand. Submitting skips the rest of the animation, then moves on.
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        match cmd {
+            Cmd::Tick if !self.finished() => {
+                self.shown += 1;
+                CmdResult::Changed(State::None)
+            }
+            Cmd::Submit if !self.finished() => {
+                self.shown = self.hands.len() + 1;
+                CmdResult::Changed(State::None)
+            }
+            Cmd::Submit => CmdResult::Submit(State::None),
+            _ => CmdResult::None,
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for Reveal {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd = match ev {
+            Event::Tick => Cmd::Tick,
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Cmd::Submit,
+            _ => return None,
+        };
+        match self.perform(cmd) {
+            CmdResult::Changed(_) => Some(Msg::Redraw),
+            CmdResult::Submit(_) => Some(Msg::RevealDone),
+            _ => None,
+        }
+    }
+}
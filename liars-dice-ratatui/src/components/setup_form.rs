@@ -0,0 +1,275 @@
+use crate::components::choice_panel::ERROR;
+use crate::setup::{GameSetup, MAX_SEATS, MAX_STARTING_DICE, MIN_SEATS, SeatSetup};
+use crate::tuirealm_data::{Msg, UserEvent};
+use liars_dice_lib::RuleVariant;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use tuirealm::command::{Cmd, CmdResult, Direction};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::Props;
+use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State};
+
+/// Bots offered for every seat, besides any the form was opened with
+const AGENTS: [&str; 3] = ["random", "ismcts:100", "ismcts:1000"];
+const MAX_NAME: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Seats,
+    Seat(usize),
+    Dice,
+    Rules,
+    Seed,
+    Start,
+}
+
+/// Configures a game before it starts. Enter sends [`Msg::StartGame`] from any row.
+pub struct SetupForm {
+    props: Props,
+    setup: GameSetup,
+    /// Who can play a seat, where `None` is someone at the keyboard
+    kinds: Vec<Option<String>>,
+    seed: String,
+    row: usize,
+}
+
+impl SetupForm {
+    pub fn new(setup: GameSetup) -> Self {
+        let mut kinds: Vec<Option<String>> = vec![None];
+        kinds.extend(AGENTS.iter().map(|agent| Some(agent.to_string())));
+        for seat in &setup.seats {
+            if !kinds.contains(&seat.agent) {
+                kinds.push(seat.agent.clone());
+            }
+        }
+        Self {
+            props: Props::default(),
+            seed: setup.seed.map(|seed| seed.to_string()).unwrap_or_default(),
+            setup,
+            kinds,
+            row: 0,
+        }
+    }
+
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = vec![Row::Seats];
+        rows.extend((0..self.setup.seats.len()).map(Row::Seat));
+        rows.extend([Row::Dice, Row::Rules, Row::Seed, Row::Start]);
+        rows
+    }
+
+    fn focused(&self) -> Row {
+        self.rows()[self.row]
+    }
+
+    fn adjust(&mut self, step: isize) {
+        match self.focused() {
+            Row::Seats => {
+                let seats = (self.setup.seats.len() as isize + step)
+                    .clamp(MIN_SEATS as isize, MAX_SEATS as isize)
+                    as usize;
+                while self.setup.seats.len() < seats {
+                    let name = format!("Player {}", self.setup.seats.len() + 1);
+                    self.setup.seats.push(SeatSetup::human(name));
+                }
+                self.setup.seats.truncate(seats);
+            }
+            Row::Seat(i) => {
+                let seat = &mut self.setup.seats[i];
+                let kind = self.kinds.iter().position(|kind| *kind == seat.agent);
+                let next =
+                    (kind.unwrap_or(0) as isize + step).rem_euclid(self.kinds.len() as isize);
+                seat.agent = self.kinds[next as usize].clone();
+            }
+            Row::Dice => {
+                self.setup.starting_dice = (self.setup.starting_dice as isize + step)
+                    .clamp(1, MAX_STARTING_DICE as isize)
+                    as u8
+            }
+            Row::Rules => {
+                self.setup.rules = match self.setup.rules {
+                    RuleVariant::Standard => RuleVariant::OnesWild,
+                    // OpenSpiel rules aren't offered, as the hints and choices assume SpotOn can be played
+                    RuleVariant::OnesWild | RuleVariant::OpenSpiel => RuleVariant::Standard,
+                }
+            }
+            Row::Seed | Row::Start => (),
+        }
+    }
+
+    fn type_char(&mut self, c: char) {
+        match self.focused() {
+            Row::Seat(i) if self.setup.seats[i].name.chars().count() < MAX_NAME => {
+                self.setup.seats[i].name.push(c)
+            }
+            Row::Seed if c.is_ascii_digit() => {
+                self.seed.push(c);
+                if self.seed.parse::<u64>().is_err() {
+                    self.seed.pop();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn delete(&mut self) {
+        match self.focused() {
+            Row::Seat(i) => {
+                self.setup.seats[i].name.pop();
+            }
+            Row::Seed => {
+                self.seed.pop();
+            }
+            _ => (),
+        }
+    }
+
+    /// The settings as entered so far
+    fn setup(&self) -> GameSetup {
+        GameSetup {
+            seed: self.seed.parse().ok(),
+            ..self.setup.clone()
+        }
+    }
+
+    fn line(&self, row: Row, label: String, value: String) -> Line<'static> {
+        let style = match self.focused() == row {
+            true => Style::default().add_modifier(Modifier::REVERSED),
+            false => Style::default(),
+        };
+        Line::from(vec![
+            Span::raw(format!("{label:<16}")),
+            Span::styled(value, style),
+        ])
+    }
+}
+
+impl MockComponent for SetupForm {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![];
+        for row in self.rows() {
+            let line = match row {
+                Row::Seats => self.line(
+                    row,
+                    "Seats".to_string(),
+                    format!("< {} >", self.setup.seats.len()),
+                ),
+                Row::Seat(i) => {
+                    let seat = &self.setup.seats[i];
+                    let kind = seat.agent.as_deref().unwrap_or("human");
+                    self.line(
+                        row,
+                        format!("  Seat {}", i + 1),
+                        format!("{:<width$} < {kind} >", seat.name, width = MAX_NAME),
+                    )
+                }
+                Row::Dice => self.line(
+                    row,
+                    "Starting dice".to_string(),
+                    format!("< {} >", self.setup.starting_dice),
+                ),
+                Row::Rules => {
+                    let rules = match self.setup.rules {
+                        RuleVariant::Standard => "standard",
+                        RuleVariant::OnesWild => "ones wild",
+                        RuleVariant::OpenSpiel => "openspiel",
+                    };
+                    self.line(row, "Rules".to_string(), format!("< {rules} >"))
+                }
+                Row::Seed => {
+                    let seed = match self.seed.is_empty() {
+                        true => "random".to_string(),
+                        false => self.seed.clone(),
+                    };
+                    self.line(row, "Seed".to_string(), seed)
+                }
+                Row::Start => self.line(row, String::new(), "[ Start ]".to_string()),
+            };
+            lines.push(line);
+        }
+        lines.push(Line::raw(""));
+        if let Some(AttrValue::String(error)) = self.props.get(ERROR)
+            && !error.is_empty()
+        {
+            lines.push(Line::styled(error, Style::default().fg(Color::Red)));
+        }
+        lines.push(Line::styled(
+            "↑/↓ move, ←/→ change, type to edit names and the seed, Enter to start, Esc to quit",
+            Style::default().fg(Color::DarkGray),
+        ));
+        let block = Block::default().borders(Borders::ALL).title("New game");
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        let rows = self.rows().len();
+        match cmd {
+            Cmd::Move(Direction::Up) => self.row = (self.row + rows - 1) % rows,
+            Cmd::Move(Direction::Down) => self.row = (self.row + 1) % rows,
+            Cmd::Move(Direction::Left) => self.adjust(-1),
+            Cmd::Move(Direction::Right) => self.adjust(1),
+            Cmd::Type(c) => self.type_char(c),
+            Cmd::Delete => self.delete(),
+            Cmd::Submit => return CmdResult::Submit(State::None),
+            _ => return CmdResult::None,
+        }
+        // Removing seats can leave the focus past the last row
+        self.row = self.row.min(self.rows().len() - 1);
+        self.props.set(ERROR, AttrValue::String(String::new()));
+        CmdResult::Changed(State::None)
+    }
+}
+
+impl Component<Msg, UserEvent> for SetupForm {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Up | Key::BackTab,
+                ..
+            }) => Cmd::Move(Direction::Up),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down | Key::Tab,
+                ..
+            }) => Cmd::Move(Direction::Down),
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => Cmd::Move(Direction::Left),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => Cmd::Move(Direction::Right),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(c), ..
+            }) => Cmd::Type(c),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => Cmd::Delete,
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Cmd::Submit,
+            // Letters go into the names here, so Esc stands in for the quit key
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => return Some(Msg::AppClose),
+            _ => return None,
+        };
+        match self.perform(cmd) {
+            CmdResult::Submit(_) => Some(Msg::StartGame(self.setup())),
+            CmdResult::Changed(_) => Some(Msg::Redraw),
+            _ => None,
+        }
+    }
+}
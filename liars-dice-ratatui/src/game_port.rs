@@ -0,0 +1,27 @@
+use crate::tuirealm_data::UserEvent;
+use std::sync::mpsc::{Receiver, Sender, TryRecvError, channel};
+use tuirealm::Event;
+use tuirealm::listener::{ListenerError, ListenerResult, Poll};
+
+/// Delivers game updates to components as user events
+pub struct GamePort {
+    receiver: Receiver<UserEvent>,
+}
+
+impl GamePort {
+    /// The port and the sender that feeds it
+    pub fn new() -> (Self, Sender<UserEvent>) {
+        let (sender, receiver) = channel();
+        (Self { receiver }, sender)
+    }
+}
+
+impl Poll<UserEvent> for GamePort {
+    fn poll(&mut self) -> ListenerResult<Option<Event<UserEvent>>> {
+        match self.receiver.try_recv() {
+            Ok(event) => Ok(Some(Event::User(event))),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(ListenerError::PollFailed),
+        }
+    }
+}
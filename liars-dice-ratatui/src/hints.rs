@@ -0,0 +1,89 @@
+use liars_dice_lib::{Dice, DiceCall, LiarsDiceGame};
+use uuid::Uuid;
+
+/// Odds for the current player, counting only their own dice and how many dice the others hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    /// The bid on the table, with the chance it holds and the chance it is exactly right
+    pub bid: Option<(DiceCall, f64, f64)>,
+    /// Dice gained by others minus dice lost, on average, for calling Bullshit and for calling SpotOn
+    pub bullshit_ev: f64,
+    pub spot_on_ev: f64,
+    /// The raise most likely to hold, with that chance
+    pub best_raise: Option<(DiceCall, f64)>,
+    pub suggestion: Option<DiceCall>,
+}
+
+impl Hint {
+    pub fn for_player(game: &LiarsDiceGame, player_id: &Uuid) -> Self {
+        let dice = game.player_dices();
+        let own = &dice[player_id];
+        let unseen = dice.values().map(Vec::len).sum::<usize>() - own.len();
+        let chances = |count: usize, face: Dice| {
+            let mine = own
+                .iter()
+                .filter(|die| game.rules().matches(**die, face))
+                .count();
+            let p = game.rules().match_chance(face);
+            let needed = count.saturating_sub(mine);
+            let exact = match count >= mine {
+                true => binomial(unseen, needed, p),
+                false => 0.0,
+            };
+            let at_least: f64 = (needed..=unseen).map(|k| binomial(unseen, k, p)).sum();
+            (at_least, exact)
+        };
+        let bid = game
+            .previous_calls()
+            .first()
+            .and_then(|(_, _, call)| *call)
+            .map(|call| match call {
+                DiceCall::Increase { count, value } => {
+                    let (at_least, exact) = chances(count, value);
+                    (call, at_least, exact)
+                }
+                _ => unreachable!("Challenges end the round"),
+            });
+        let opponents = game.current_players().len().saturating_sub(1) as f64;
+        let (bullshit_ev, spot_on_ev) = match bid {
+            Some((_, holds, exact)) => ((1.0 - holds) - holds, exact * opponents - (1.0 - exact)),
+            None => (0.0, 0.0),
+        };
+        // Iterating backwards keeps the lowest of equally likely raises
+        let best_raise = game
+            .legal_calls()
+            .into_iter()
+            .rev()
+            .filter_map(|call| match call {
+                DiceCall::Increase { count, value } => Some((call, chances(count, value).0)),
+                _ => None,
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let raise_ev = best_raise.map_or(f64::NEG_INFINITY, |(_, holds)| holds - (1.0 - holds));
+        let suggestion = [
+            (best_raise.map(|(call, _)| call), raise_ev),
+            (bid.map(|_| DiceCall::Bullshit), bullshit_ev),
+            (bid.map(|_| DiceCall::SpotOn), spot_on_ev),
+        ]
+        .into_iter()
+        .filter_map(|(call, ev)| call.map(|call| (call, ev)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(call, _)| call);
+        Self {
+            bid,
+            bullshit_ev,
+            spot_on_ev,
+            best_raise,
+            suggestion,
+        }
+    }
+}
+
+/// Chance of exactly `k` successes in `n` tries
+pub(crate) fn binomial(n: usize, k: usize, p: f64) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let ways: f64 = (0..k).map(|i| (n - i) as f64 / (i + 1) as f64).product();
+    ways * p.powi(k as i32) * (1.0 - p).powi((n - k) as i32)
+}
@@ -0,0 +1,227 @@
+use std::path::PathBuf;
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+/// Something a key does from any screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    NewGame,
+    ToggleHints,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [
+        Action::Quit,
+        Action::Help,
+        Action::NewGame,
+        Action::ToggleHints,
+    ];
+
+    /// What the action is called in the keymap file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::NewGame => "new_game",
+            Action::ToggleHints => "hints",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Show or hide these keys",
+            Action::NewGame => "Back to the setup form",
+            Action::ToggleHints => "Show or hide the odds on your turn",
+        }
+    }
+}
+
+/// Which keys trigger which actions.
+/// Any action left out of the keymap file keeps its default keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(KeyEvent, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let defaults = [
+            ("q", Action::Quit),
+            ("ctrl+c", Action::Quit),
+            ("?", Action::Help),
+            ("n", Action::NewGame),
+            ("h", Action::ToggleHints),
+        ];
+        Self {
+            bindings: defaults
+                .into_iter()
+                .map(|(key, action)| (parse_key(key).unwrap(), action))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Where the keymap is read from, `liars-dice/keys.toml` under the XDG config dir
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("liars-dice").join("keys.toml"))
+    }
+
+    /// Reads the keymap file, or falls back on the defaults when there isn't one
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::path().filter(|path| path.exists()) else {
+            return Ok(Self::default());
+        };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+        Self::parse(&text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Reads a table of action names to a key or a list of keys, such as `quit = ["q", "ctrl+c"]`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let table: toml::Table = text.parse().map_err(|e| format!("{e}"))?;
+        let mut keymap = Self::default();
+        for (name, value) in table {
+            let action = Action::ALL
+                .into_iter()
+                .find(|action| action.name() == name)
+                .ok_or_else(|| format!("No action called {name}"))?;
+            let keys = match value {
+                toml::Value::String(key) => vec![key],
+                toml::Value::Array(keys) => keys
+                    .into_iter()
+                    .map(|key| match key {
+                        toml::Value::String(key) => Ok(key),
+                        _ => Err(format!("Keys for {name} should be strings")),
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(format!("Keys for {name} should be a string or a list")),
+            };
+            keymap.bindings.retain(|(_, bound)| *bound != action);
+            for key in keys {
+                keymap.bindings.push((parse_key(&key)?, action));
+            }
+        }
+        for (i, (key, action)) in keymap.bindings.iter().enumerate() {
+            if let Some((_, other)) = keymap.bindings[..i].iter().find(|(k, _)| k == key) {
+                return Err(format!(
+                    "{} is bound to both {} and {}",
+                    key_name(key),
+                    other.name(),
+                    action.name()
+                ));
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// What `key` does, if anything
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        let key = without_shift(*key);
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, action)| *action)
+    }
+
+    /// Every key bound to `action`, as they read in the help
+    pub fn keys(&self, action: Action) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(key, _)| key_name(key))
+            .collect()
+    }
+
+    /// Every bound key, the way terminals send it
+    pub fn key_events(&self) -> Vec<KeyEvent> {
+        let mut events = vec![];
+        for (key, _) in &self.bindings {
+            events.push(*key);
+            // Terminals differ over whether `?` or `Q` come with shift held
+            if let Key::Char(_) = key.code {
+                events.push(KeyEvent::new(key.code, key.modifiers | KeyModifiers::SHIFT));
+            }
+        }
+        events
+    }
+}
+
+/// Shift is part of the character already, so `?` matches however the terminal reports it
+fn without_shift(mut key: KeyEvent) -> KeyEvent {
+    if let Key::Char(_) = key.code {
+        key.modifiers.remove(KeyModifiers::SHIFT);
+    }
+    key
+}
+
+/// Reads keys such as `q`, `?`, `esc`, `f1` or `ctrl+c`.
+/// Shift only goes with named keys, as `Q` already says shift was held.
+pub fn parse_key(text: &str) -> Result<KeyEvent, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text;
+    // A lone `+` is a key rather than a separator
+    while let Some((modifier, key)) = rest.split_once('+').filter(|(_, key)| !key.is_empty()) {
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return Err(format!("{modifier} in {text} is not a modifier")),
+        };
+        rest = key;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => Key::Char(c),
+        _ => match rest.to_lowercase().as_str() {
+            "space" => Key::Char(' '),
+            "esc" => Key::Esc,
+            "enter" => Key::Enter,
+            "tab" => Key::Tab,
+            "backtab" => Key::BackTab,
+            "backspace" => Key::Backspace,
+            "delete" => Key::Delete,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => Key::Function(n),
+                _ => return Err(format!("{text} is not a key")),
+            },
+        },
+    };
+    if let Key::Char(_) = code
+        && modifiers.contains(KeyModifiers::SHIFT)
+    {
+        return Err(format!("{text} should be written as the shifted character"));
+    }
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+/// How a key reads in the help, the same way it is written in the keymap file
+pub fn key_name(key: &KeyEvent) -> String {
+    let mut name = String::new();
+    for (modifier, prefix) in [
+        (KeyModifiers::CONTROL, "ctrl+"),
+        (KeyModifiers::ALT, "alt+"),
+        (KeyModifiers::SHIFT, "shift+"),
+    ] {
+        if key.modifiers.contains(modifier) {
+            name.push_str(prefix);
+        }
+    }
+    match key.code {
+        Key::Char(' ') => name.push_str("space"),
+        Key::Char(c) => name.push(c),
+        Key::Function(n) => name.push_str(&format!("f{n}")),
+        code => name.push_str(&format!("{code:?}").to_lowercase()),
+    }
+    name
+}
@@ -1,21 +1,84 @@
+use crate::bots::AGENTS;
+use crate::keymap::Keymap;
 use crate::model::Model;
-use crate::tuirealm_data::Msg;
+use crate::setup::{GameSetup, MAX_SEATS, MAX_STARTING_DICE, MIN_SEATS, SeatSetup};
+use liars_dice_lib::RuleVariant;
+use liars_dice_lib::record::GameRecord;
+use std::process::exit;
 use tuirealm::PollStrategy;
 
+mod bots;
 pub mod components;
+mod game_port;
+mod hints;
+mod keymap;
 pub mod model;
+mod setup;
+#[cfg(test)]
+mod test;
 mod tuirealm_data;
 
+const USAGE: &str = "usage: liars-dice-ratatui [--dice <n>] [--ones-wild] [--seed <n>] [<seat>...]
+       liars-dice-ratatui --replay <record.ldr> [--show-dice]
+Fills in the setup form, which can still be changed before the game starts, or steps through a recorded game.
+seats: <name> for someone at the keyboard, or <name>=<agent> for a bot
+keys: read from liars-dice/keys.toml under the config dir, press ? to list them
+agents: ";
+
 pub fn main() {
-    let mut model = Model::default();
+    let mut setup = GameSetup::default();
+    let mut seats = vec![];
+    let mut replay = None;
+    let mut show_dice = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| fail(&format!("{arg} needs a value")))
+        };
+        match arg.as_str() {
+            "--dice" => {
+                let dice: u8 = parse_number(&value());
+                setup.starting_dice = dice.clamp(1, MAX_STARTING_DICE);
+            }
+            "--seed" => setup.seed = Some(parse_number(&value())),
+            "--ones-wild" => setup.rules = RuleVariant::OnesWild,
+            "--replay" => replay = Some(value()),
+            "--show-dice" => show_dice = true,
+            "--help" | "-h" => {
+                println!("{USAGE}{AGENTS}");
+                return;
+            }
+            _ => seats.push(match arg.split_once('=') {
+                Some((name, agent)) => SeatSetup {
+                    name: name.to_string(),
+                    agent: Some(agent.to_string()),
+                },
+                None => SeatSetup::human(arg),
+            }),
+        }
+    }
+    match seats.len() {
+        0 => (),
+        MIN_SEATS..=MAX_SEATS => setup.seats = seats,
+        _ => fail(&format!("Need between {MIN_SEATS} and {MAX_SEATS} seats")),
+    }
+    let keymap = Keymap::load().unwrap_or_else(|e| fail(&e));
+    let mut model = match replay {
+        Some(path) => {
+            let record: GameRecord = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| fail(&format!("Could not read {path}: {e}")))
+                .parse()
+                .unwrap_or_else(|e| fail(&format!("Could not read {path}: {e}")));
+            Model::replay(&record, show_dice, keymap)
+                .unwrap_or_else(|e| fail(&format!("Could not replay {path}: {e}")))
+        }
+        None => Model::new(setup, keymap),
+    };
     while !model.quit {
         if let Ok(m) = model.app.tick(PollStrategy::Once) {
             for message in m {
-                match message {
-                    Msg::AppClose => {
-                        model.quit = true;
-                    }
-                }
+                model.update(message);
             }
         }
         if model.redraw {
@@ -23,3 +86,14 @@ pub fn main() {
         }
     }
 }
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("{value} is not a valid number")))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}{AGENTS}");
+    exit(1)
+}
@@ -1,10 +1,41 @@
-use crate::components::player_table::PlayerTableComponent;
+use crate::bots::BotWorker;
+use crate::components::bid_history::{BidHistory, HistoryEntry};
+use crate::components::bot_status::BotStatus;
+use crate::components::choice_panel::{CONFIRMING, ChoicePanel, ERROR, describe};
+use crate::components::hand::Hand;
+use crate::components::help_overlay::HelpOverlay;
+use crate::components::hint_overlay::HintOverlay;
+use crate::components::pass_screen::PassScreen;
+use crate::components::player_table::{PlayerTableComponent, SeatRow};
+use crate::components::replay_viewer::ReplayViewer;
+use crate::components::reveal::Reveal;
+use crate::components::setup_form::SetupForm;
+use crate::game_port::GamePort;
+use crate::hints::Hint;
+use crate::keymap::Keymap;
+use crate::setup::GameSetup;
 use crate::tuirealm_data::{Id, Msg, UserEvent};
-use ratatui::layout::{Constraint, Layout};
+use liars_dice_lib::record::GameRecord;
+use liars_dice_lib::{
+    CallOutcome, ConfirmChoice, Dice, DiceCall, GameEvent, LiarsDiceGame, LiarsDiceState,
+};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::prelude::Direction;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::mpsc::Sender;
 use std::time::Duration;
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::terminal::{CrosstermTerminalAdapter, TerminalAdapter, TerminalBridge};
-use tuirealm::{Application, EventListenerCfg};
+use tuirealm::{
+    Application, AttrValue, Component, EventListenerCfg, Sub, SubClause, SubEventClause,
+};
+use uuid::Uuid;
+
+/// How often the reveal shows another player's dice
+const TICK: Duration = Duration::from_millis(400);
+/// The least time a bot's turn takes, so people can follow the game
+const BOT_DELAY: Duration = Duration::from_millis(800);
 
 pub struct Model<T>
 where
@@ -14,6 +45,40 @@ where
     pub terminal: TerminalBridge<T>,
     pub quit: bool,
     pub redraw: bool,
+    /// Until the setup form is submitted this is only a stand-in, built from the form's defaults
+    pub game: LiarsDiceGame,
+    pub names: BTreeMap<Uuid, String>,
+    /// Feeds the game port, so components hear about the game changing
+    events: Sender<UserEvent>,
+    /// A call the engine accepted, waiting for the player to confirm it
+    pending: Option<ConfirmChoice>,
+    /// Seats played by agents rather than from the keyboard
+    bots: BTreeSet<Uuid>,
+    worker: BotWorker,
+    stage: Stage,
+    /// Whether the odds are drawn over the table on a human's turn
+    hints: bool,
+    /// Whether the list of keys is drawn over everything
+    help: bool,
+    /// How the setup form is filled in when going back to it
+    setup: GameSetup,
+    /// Counts turns, so a bot's call that arrives after a new game has started can be ignored
+    turn: usize,
+}
+
+/// What the space under the table is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    /// The setup form fills the screen
+    Setup,
+    /// The pass screen hides the hand, until someone presses Enter
+    HandingOver,
+    HumanTurn,
+    BotTurn,
+    /// Everyone's dice are on show after a challenge
+    Reveal,
+    /// A recorded game fills the screen, and there is nothing to play
+    Replay,
 }
 
 impl<T> Model<T>
@@ -21,24 +86,381 @@ where
     T: TerminalAdapter,
 {
     pub fn do_redraw(&mut self) {
+        self.redraw = false;
         self.terminal
             .draw(|frame| {
-                let chunks = Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints([Constraint::Fill(1), Constraint::Fill(2)]);
-                self.app.view(&Id::PlayerTable, frame, frame.area());
+                match self.stage {
+                    Stage::Setup => self.app.view(&Id::SetupForm, frame, frame.area()),
+                    Stage::Replay => self.app.view(&Id::ReplayViewer, frame, frame.area()),
+                    Stage::Reveal => {
+                        // Room for the bid, a line per hand, the tally, the news and the borders
+                        let height = 2 * self.names.len() as u16 + 6;
+                        let rows = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([Constraint::Fill(1), Constraint::Length(height)])
+                            .split(frame.area());
+                        view_table(&mut self.app, frame, rows[0]);
+                        self.app.view(&Id::Reveal, frame, rows[1]);
+                    }
+                    Stage::HandingOver | Stage::BotTurn | Stage::HumanTurn => {
+                        view_play(&mut self.app, frame, self.stage, self.hints)
+                    }
+                }
+                if self.help {
+                    let area = frame.area();
+                    let width = area.width.min(56);
+                    let height = area.height.min(HelpOverlay::height());
+                    let centre = Rect::new(
+                        area.x + (area.width - width) / 2,
+                        area.y + (area.height - height) / 2,
+                        width,
+                        height,
+                    );
+                    self.app.view(&Id::HelpOverlay, frame, centre);
+                }
+            })
+            .unwrap();
+    }
+
+    pub fn update(&mut self, msg: Msg) {
+        self.redraw = true;
+        match msg {
+            Msg::AppClose => self.quit = true,
+            Msg::Redraw => (),
+            Msg::StartGame(setup) => {
+                if let Err(e) = self.start_game(&setup) {
+                    self.app
+                        .attr(&Id::SetupForm, ERROR, AttrValue::String(e))
+                        .unwrap();
+                }
+            }
+            Msg::ProposeCall(call) => {
+                let LiarsDiceState::PlayerTurn(turn) = self.game.get_state() else {
+                    return;
+                };
+                let (attr, value) = match turn.propose_choice(call) {
+                    Ok(choice) => {
+                        self.pending = Some(choice);
+                        (CONFIRMING, AttrValue::Flag(true))
+                    }
+                    Err(reason) => (ERROR, AttrValue::String(reason.to_string())),
+                };
+                self.app.attr(&Id::ChoicePanel, attr, value).unwrap();
+            }
+            Msg::ConfirmCall => {
+                if let Some(choice) = self.pending.take() {
+                    self.play(choice);
+                }
+            }
+            Msg::CancelCall => self.pending = None,
+            Msg::HandedOver => match self.game.get_state() {
+                LiarsDiceState::GameComplete(complete) => {
+                    complete.play_again();
+                    self.game_changed();
+                    self.next_turn();
+                }
+                _ => self.start_turn(),
+            },
+            Msg::RevealDone => self.next_turn(),
+            Msg::ToggleHints if !matches!(self.stage, Stage::Setup | Stage::Replay) => {
+                self.hints = !self.hints
+            }
+            Msg::ToggleHints => (),
+            Msg::ToggleHelp => self.help = !self.help,
+            Msg::NewGame => self.new_game(),
+            Msg::BotCalled {
+                turn,
+                player_id,
+                call,
+            } => {
+                if self.stage != Stage::BotTurn || turn != self.turn {
+                    return;
+                }
+                let LiarsDiceState::PlayerTurn(state) = self.game.get_state() else {
+                    return;
+                };
+                assert_eq!(
+                    state.player_id, player_id,
+                    "Bot answered for the wrong seat"
+                );
+                let choice = state
+                    .propose_choice(call)
+                    .expect("Bots only make legal calls");
+                self.play(choice);
+            }
+        }
+    }
+
+    /// Seats everyone from `setup` at a new game, each bot with a fresh agent
+    fn start_game(&mut self, setup: &GameSetup) -> Result<(), String> {
+        let seats = setup.seats()?;
+        let mut names = BTreeMap::new();
+        let mut agents = BTreeMap::new();
+        for (id, seat) in setup.player_ids().into_iter().zip(seats) {
+            names.insert(id, seat.name);
+            if let Some(agent) = seat.agent {
+                agents.insert(id, agent);
+            }
+        }
+        self.app.umount(&Id::SetupForm).unwrap();
+        self.setup = setup.clone();
+        self.game = setup.game();
+        self.names = names;
+        self.bots = agents.keys().copied().collect();
+        // Dropping the old worker stops its thread, and the turn count rules out anything it still sends
+        self.worker = BotWorker::spawn(agents, self.events.clone(), BOT_DELAY);
+        self.pending = None;
+        self.game_changed();
+        self.next_turn();
+        Ok(())
+    }
+
+    /// Abandons the game for the setup form, filled in as it was last submitted
+    fn new_game(&mut self) {
+        // As when starting a game, the worker is replaced so no bot keeps thinking
+        self.worker = BotWorker::spawn(BTreeMap::new(), self.events.clone(), BOT_DELAY);
+        self.pending = None;
+        let form = SetupForm::new(self.setup.clone());
+        self.app
+            .remount(Id::SetupForm, Box::new(form), vec![])
+            .unwrap();
+        self.app.active(&Id::SetupForm).unwrap();
+        self.stage = Stage::Setup;
+    }
+
+    /// Makes a call for the current player and moves on to whoever is next
+    fn play(&mut self, choice: ConfirmChoice) {
+        let before = self.game.player_dices();
+        let LiarsDiceState::PlayerTurn(turn) = self.game.get_state() else {
+            return;
+        };
+        let caller = turn.player_id;
+        let outcome = choice.confirm(turn);
+        self.game_changed();
+        match outcome {
+            Some(outcome) => self.reveal(&caller, &before, &outcome),
+            None => self.next_turn(),
+        }
+    }
+
+    /// Bots play straight on.
+    /// Humans get the pass screen when several share the keyboard, so nobody sees anyone else's dice.
+    fn next_turn(&mut self) {
+        let humans = self.names.len() - self.bots.len();
+        let bot_next = self
+            .game
+            .current_player()
+            .is_some_and(|id| self.bots.contains(&id));
+        match self.game.is_complete() || (humans > 1 && !bot_next) {
+            true => self.hand_over(),
+            false => self.start_turn(),
+        }
+    }
+
+    fn name(&self, player_id: &Uuid) -> String {
+        self.names
+            .get(player_id)
+            .cloned()
+            .unwrap_or_else(|| player_id.to_string())
+    }
+
+    /// Shows the latest state of the game to every component
+    fn game_changed(&mut self) {
+        let rows = SeatRow::from_game(&self.game, &self.names);
+        self.events.send(UserEvent::SeatsChanged(rows)).unwrap();
+        let entries = HistoryEntry::from_game(&self.game, &self.names);
+        self.events
+            .send(UserEvent::HistoryChanged(entries))
+            .unwrap();
+    }
+
+    /// Shows everyone's dice from the challenge that just ended the round
+    fn reveal(&mut self, caller: &Uuid, before: &BTreeMap<Uuid, Vec<Dice>>, outcome: &CallOutcome) {
+        // The challenge was the last call, and the bid it challenged the one before
+        let bid = self
+            .game
+            .history()
+            .iter()
+            .rev()
+            .filter_map(|event| match event {
+                GameEvent::Called { call, .. } => Some(*call),
+                _ => None,
             })
+            .nth(1);
+        let Some(DiceCall::Increase { count, value }) = bid else {
+            unreachable!("Challenges always follow a bid")
+        };
+        let hands = outcome
+            .player_dices
+            .iter()
+            .filter(|(_, dice)| !dice.is_empty())
+            .map(|(id, dice)| (self.name(id), dice.clone()))
+            .collect();
+        let news = self.describe_outcome(caller, before, outcome);
+        let reveal = Reveal::new(hands, count, value, self.game.rules(), news);
+        self.app
+            .remount(Id::Reveal, Box::new(reveal), vec![])
             .unwrap();
+        self.app.active(&Id::Reveal).unwrap();
+        self.stage = Stage::Reveal;
+    }
+
+    /// Who challenged, whether they were right and who lost dice
+    fn describe_outcome(
+        &self,
+        caller: &Uuid,
+        before: &BTreeMap<Uuid, Vec<Dice>>,
+        outcome: &CallOutcome,
+    ) -> Vec<String> {
+        let call = self
+            .game
+            .history()
+            .iter()
+            .rev()
+            .find_map(|event| match event {
+                GameEvent::Called { call, .. } => Some(describe(call)),
+                _ => None,
+            })
+            .expect("A challenge was just made");
+        let verdict = match outcome.correct_call {
+            true => "right",
+            false => "wrong",
+        };
+        let mut news = vec![format!(
+            "{} called {call} and was {verdict}",
+            self.name(caller)
+        )];
+        let after = self.game.player_dices();
+        for (id, dice) in before {
+            match after[id].len() {
+                0 if !dice.is_empty() => news.push(format!("{} is out", self.name(id))),
+                left if left < dice.len() => news.push(format!("{} lost a die", self.name(id))),
+                _ => (),
+            }
+        }
+        news
+    }
+
+    /// Covers the table's hand and choices with the pass screen
+    fn hand_over(&mut self) {
+        let headline = match (self.game.winner(), self.game.current_player()) {
+            (Some(winner), _) => format!("{} wins! Press Enter to play again", self.name(&winner)),
+            (None, Some(next)) => format!("Pass to {}, then press Enter", self.name(&next)),
+            (None, None) => unreachable!("Game should have started"),
+        };
+        self.app
+            .remount(Id::PassScreen, Box::new(PassScreen::new(headline)), vec![])
+            .unwrap();
+        self.app.active(&Id::PassScreen).unwrap();
+        self.stage = Stage::HandingOver;
+    }
+
+    /// Shows the current player their dice and the calls they can make, or asks their bot for a call
+    fn start_turn(&mut self) {
+        let Some(player_id) = self.game.current_player() else {
+            return;
+        };
+        self.turn += 1;
+        if self.bots.contains(&player_id) {
+            let status = BotStatus::new(self.name(&player_id));
+            self.app
+                .remount(
+                    Id::BotStatus,
+                    Box::new(status),
+                    vec![Sub::new(
+                        SubEventClause::User(UserEvent::BotCalled {
+                            turn: 0,
+                            player_id: Uuid::nil(),
+                            call: DiceCall::Bullshit,
+                        }),
+                        SubClause::Always,
+                    )],
+                )
+                .unwrap();
+            self.app.active(&Id::BotStatus).unwrap();
+            self.worker.request(self.turn, &self.game, player_id);
+            self.stage = Stage::BotTurn;
+            return;
+        }
+        let dice = self.game.player_dices();
+        let hand = Hand::new(self.name(&player_id), dice[&player_id].clone());
+        let dice_in_play = dice.values().map(Vec::len).sum();
+        let panel = ChoicePanel::new(self.game.legal_calls(), dice_in_play);
+        self.app.remount(Id::Hand, Box::new(hand), vec![]).unwrap();
+        self.app
+            .remount(Id::ChoicePanel, Box::new(panel), vec![])
+            .unwrap();
+        let overlay = HintOverlay::new(Hint::for_player(&self.game, &player_id));
+        self.app
+            .remount(Id::HintOverlay, Box::new(overlay), vec![])
+            .unwrap();
+        self.app.active(&Id::ChoicePanel).unwrap();
+        self.stage = Stage::HumanTurn;
     }
 }
 
-impl Default for Model<CrosstermTerminalAdapter> {
-    fn default() -> Self {
+impl Model<CrosstermTerminalAdapter> {
+    /// Opens on the setup form, filled in from `setup`
+    pub fn new(setup: GameSetup, keymap: Keymap) -> Self {
+        let form = SetupForm::new(setup.clone());
+        let game = setup.game();
+        Self::open(
+            game,
+            setup,
+            keymap,
+            Id::SetupForm,
+            Box::new(form),
+            Stage::Setup,
+        )
+    }
+
+    /// Opens a recorded game to step through, instead of playing one
+    pub fn replay(
+        record: &GameRecord,
+        show_dice: bool,
+        keymap: Keymap,
+    ) -> Result<Self, &'static str> {
+        let game = record.replay()?;
+        let viewer = ReplayViewer::new(record, &game, show_dice);
+        Ok(Self::open(
+            game,
+            GameSetup::default(),
+            keymap,
+            Id::ReplayViewer,
+            Box::new(viewer),
+            Stage::Replay,
+        ))
+    }
+
+    /// Starts the interface on `screen`, which fills the terminal until it hands over to something else
+    fn open(
+        game: LiarsDiceGame,
+        setup: GameSetup,
+        keymap: Keymap,
+        id: Id,
+        screen: Box<dyn Component<Msg, UserEvent>>,
+        stage: Stage,
+    ) -> Self {
+        let (port, events) = GamePort::new();
+        let mut app = Self::init_app(port, keymap);
+        app.mount(id.clone(), screen, vec![]).unwrap();
+        app.active(&id).unwrap();
         Self {
-            app: Self::init_app(),
+            app,
             quit: false,
             redraw: true,
             terminal: TerminalBridge::init_crossterm().expect("Cannot initialize terminal"),
+            game,
+            names: BTreeMap::new(),
+            worker: BotWorker::spawn(BTreeMap::new(), events.clone(), BOT_DELAY),
+            events,
+            pending: None,
+            bots: BTreeSet::new(),
+            stage,
+            hints: false,
+            help: false,
+            setup,
+            turn: 0,
         }
     }
 }
@@ -47,23 +469,103 @@ impl<T> Model<T>
 where
     T: TerminalAdapter,
 {
-    fn init_app() -> Application<Id, Msg, UserEvent> {
+    fn init_app(port: GamePort, keymap: Keymap) -> Application<Id, Msg, UserEvent> {
         let mut app: Application<Id, Msg, UserEvent> = Application::init(
             EventListenerCfg::default()
                 .crossterm_input_listener(Duration::from_millis(20), 3)
                 .poll_timeout(Duration::from_millis(10))
-                .tick_interval(Duration::from_secs(1)),
+                .tick_interval(TICK)
+                .add_port(Box::new(port), Duration::from_millis(10), 8),
         );
         app.mount(
             Id::PlayerTable,
             Box::new(PlayerTableComponent::default()),
-            vec![],
+            vec![Sub::new(
+                SubEventClause::User(UserEvent::SeatsChanged(vec![])),
+                SubClause::Always,
+            )],
         )
         .unwrap();
+        let scroll_keys = [Key::PageUp, Key::PageDown, Key::End];
+        let mut subs: Vec<_> = scroll_keys
+            .into_iter()
+            .map(|key| Sub::new(SubEventClause::Keyboard(key.into()), SubClause::Always))
+            .collect();
+        subs.push(Sub::new(
+            SubEventClause::User(UserEvent::HistoryChanged(vec![])),
+            SubClause::Always,
+        ));
+        app.mount(Id::BidHistory, Box::new(BidHistory::default()), subs)
+            .unwrap();
+        app.mount(Id::HintOverlay, Box::new(HintOverlay::default()), vec![])
+            .unwrap();
+        let subs = keymap.key_events().into_iter().map(global_sub).collect();
+        app.mount(Id::HelpOverlay, Box::new(HelpOverlay::new(keymap)), subs)
+            .unwrap();
         app
     }
+}
 
-    fn redraw(&mut self) {
-        self.redraw = false;
+/// Keymap keys work whichever component has focus,
+/// except that plain letters are left to the setup form's name fields while it is up
+fn global_sub(key: KeyEvent) -> Sub<Id, UserEvent> {
+    let typed = matches!(key.code, Key::Char(_))
+        && !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+    let clause = match typed {
+        true => SubClause::Not(Box::new(SubClause::IsMounted(Id::SetupForm))),
+        false => SubClause::Always,
+    };
+    Sub::new(SubEventClause::Keyboard(key), clause)
+}
+
+/// The table, with the hand and choices under it, or whatever stands in for them
+fn view_play(
+    app: &mut Application<Id, Msg, UserEvent>,
+    frame: &mut Frame,
+    stage: Stage,
+    hints: bool,
+) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Length(4),
+        ])
+        .split(frame.area());
+    view_table(app, frame, rows[0]);
+    let below = rows[1].union(rows[2]);
+    match stage {
+        Stage::HandingOver => app.view(&Id::PassScreen, frame, below),
+        Stage::BotTurn => app.view(&Id::BotStatus, frame, below),
+        Stage::HumanTurn => {
+            app.view(&Id::Hand, frame, rows[1]);
+            app.view(&Id::ChoicePanel, frame, rows[2]);
+            if hints {
+                // Tucked into the bottom right corner of the table
+                let width = rows[0].width.min(44);
+                let height = rows[0].height.min(8);
+                let corner = Rect::new(
+                    rows[0].right() - width,
+                    rows[0].bottom() - height,
+                    width,
+                    height,
+                );
+                app.view(&Id::HintOverlay, frame, corner);
+            }
+        }
+        Stage::Setup | Stage::Replay | Stage::Reveal => unreachable!("Drawn full screen"),
     }
 }
+
+/// The player table with the bid history beside it
+fn view_table(app: &mut Application<Id, Msg, UserEvent>, frame: &mut Frame, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Fill(1), Constraint::Fill(2)])
+        .split(area);
+    app.view(&Id::BidHistory, frame, chunks[0]);
+    app.view(&Id::PlayerTable, frame, chunks[1]);
+}
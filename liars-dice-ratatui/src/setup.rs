@@ -0,0 +1,91 @@
+use crate::bots::{Seat, parse_agent};
+use liars_dice_lib::{LiarsDiceGame, LiarsDiceState, RuleVariant};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use uuid::Uuid;
+
+pub const MIN_SEATS: usize = 2;
+pub const MAX_SEATS: usize = 6;
+pub const MAX_STARTING_DICE: u8 = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatSetup {
+    pub name: String,
+    /// The agent playing this seat, as in `ismcts:500`, or `None` for someone at the keyboard
+    pub agent: Option<String>,
+}
+
+impl SeatSetup {
+    pub fn human(name: String) -> Self {
+        Self { name, agent: None }
+    }
+}
+
+/// Everything the setup form asks for before a game starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameSetup {
+    pub seats: Vec<SeatSetup>,
+    pub starting_dice: u8,
+    pub rules: RuleVariant,
+    /// Makes the dice come out the same every time, or `None` for a fresh game
+    pub seed: Option<u64>,
+}
+
+impl Default for GameSetup {
+    fn default() -> Self {
+        Self {
+            seats: (1..=MIN_SEATS)
+                .map(|i| SeatSetup::human(format!("Player {i}")))
+                .collect(),
+            starting_dice: 5,
+            rules: RuleVariant::default(),
+            seed: None,
+        }
+    }
+}
+
+impl GameSetup {
+    /// Seat ids, in seating order
+    pub fn player_ids(&self) -> Vec<Uuid> {
+        (0..self.seats.len())
+            .map(|i| Uuid::from_u128(i as u128 + 1))
+            .collect()
+    }
+
+    /// The game these settings describe, with the dice already rolled
+    pub fn game(&self) -> LiarsDiceGame {
+        let rng = match self.seed {
+            Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+            None => ChaCha12Rng::from_os_rng(),
+        };
+        let mut game =
+            LiarsDiceGame::new(rng, self.starting_dice, &self.player_ids()).with_rules(self.rules);
+        if let LiarsDiceState::Start(start) = game.get_state() {
+            start.initialise_game();
+        }
+        game
+    }
+
+    /// Every seat with its agent built, or why one of them couldn't be.
+    /// Bots are seeded from the game's seed, so a seeded game plays out the same way again.
+    pub fn seats(&self) -> Result<Vec<Seat>, String> {
+        if self.seats.iter().any(|seat| seat.name.trim().is_empty()) {
+            return Err("Every seat needs a name".to_string());
+        }
+        let mut rng = match self.seed {
+            Some(seed) => ChaCha12Rng::seed_from_u64(seed.wrapping_add(1)),
+            None => ChaCha12Rng::from_os_rng(),
+        };
+        self.seats
+            .iter()
+            .map(|seat| match &seat.agent {
+                Some(spec) => {
+                    let rng = ChaCha12Rng::from_rng(&mut rng);
+                    parse_agent(spec, self.seats.len(), self.starting_dice, rng)
+                        .map(|agent| Seat::bot(seat.name.clone(), agent))
+                }
+                None => Ok(Seat::human(seat.name.clone())),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,303 @@
+use crate::components::choice_panel::{CONFIRMING, ChoicePanel, ERROR, Field};
+use crate::hints::{Hint, binomial};
+use crate::keymap::{Action, Keymap, key_name, parse_key};
+use crate::tuirealm_data::{Msg, UserEvent};
+use liars_dice_lib::encoding::encode_call;
+use liars_dice_lib::scenario::ScenarioBuilder;
+use liars_dice_lib::{Dice, DiceCall, RuleVariant};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+use tuirealm::{AttrValue, Component, Event, MockComponent, State, StateValue};
+use uuid::Uuid;
+
+const ME: Uuid = Uuid::from_u128(1);
+const LEFT: Uuid = Uuid::from_u128(2);
+const RIGHT: Uuid = Uuid::from_u128(3);
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "{actual} should be {expected}"
+    );
+}
+
+#[test]
+fn test_binomial_sums_to_one() {
+    for n in 0..10 {
+        for p in [1.0 / 6.0, 2.0 / 6.0] {
+            let total: f64 = (0..=n).map(|k| binomial(n, k, p)).sum();
+            assert_close(total, 1.0);
+        }
+    }
+    assert_eq!(binomial(2, 3, 0.5), 0.0);
+}
+
+#[test]
+fn test_bid_covered_by_own_dice_holds() {
+    let game = ScenarioBuilder::new()
+        .player(ME, &[3, 3, 3])
+        .player(LEFT, &[2, 5])
+        .current_player(ME)
+        .bids(&[(2, 3)])
+        .build()
+        .unwrap();
+    let hint = Hint::for_player(&game, &ME);
+    let (call, holds, exact) = hint.bid.unwrap();
+    assert_eq!(
+        call,
+        DiceCall::Increase {
+            count: 2,
+            value: Dice::new(3).unwrap()
+        }
+    );
+    assert_eq!(holds, 1.0);
+    assert_eq!(exact, 0.0);
+    assert_close(hint.bullshit_ev, -1.0);
+    assert_close(hint.spot_on_ev, -1.0);
+}
+
+#[test]
+fn test_ones_wild_counts_own_ones() {
+    let scenario = ScenarioBuilder::new()
+        .player(ME, &[1, 1, 4])
+        .player(LEFT, &[2, 5])
+        .current_player(ME)
+        .bids(&[(3, 4)]);
+    // Both ones count towards fours, so the bid holds whatever the unseen dice show
+    let wild = scenario
+        .clone()
+        .rules(RuleVariant::OnesWild)
+        .build()
+        .unwrap();
+    let (_, holds, exact) = Hint::for_player(&wild, &ME).bid.unwrap();
+    assert_close(holds, 1.0);
+    assert_close(exact, 4.0 / 9.0);
+    // Otherwise both unseen dice need to be fours
+    let standard = scenario.build().unwrap();
+    let (_, holds, exact) = Hint::for_player(&standard, &ME).bid.unwrap();
+    assert_close(holds, 1.0 / 36.0);
+    assert_close(exact, 1.0 / 36.0);
+}
+
+#[test]
+fn test_spot_on_ev_counts_remaining_opponents() {
+    // RIGHT has been knocked out, so only LEFT would gain a die
+    let game = ScenarioBuilder::new()
+        .player(ME, &[2])
+        .player(LEFT, &[3])
+        .player(RIGHT, &[])
+        .starting_dice(2)
+        .current_player(ME)
+        .bids(&[(1, 5)])
+        .build()
+        .unwrap();
+    let hint = Hint::for_player(&game, &ME);
+    let exact = 1.0 / 6.0;
+    assert_close(hint.spot_on_ev, exact - (1.0 - exact));
+}
+
+#[test]
+fn test_key_names_round_trip() {
+    for name in [
+        "q",
+        "?",
+        "+",
+        "space",
+        "esc",
+        "enter",
+        "backtab",
+        "pagedown",
+        "f1",
+        "f12",
+        "ctrl+c",
+        "ctrl++",
+        "alt+x",
+        "shift+tab",
+        "ctrl+alt+delete",
+    ] {
+        assert_eq!(key_name(&parse_key(name).unwrap()), name);
+    }
+}
+
+#[test]
+fn test_parse_key() {
+    assert_eq!(
+        parse_key("ctrl+c"),
+        Ok(KeyEvent::new(Key::Char('c'), KeyModifiers::CONTROL))
+    );
+    assert_eq!(
+        parse_key("+"),
+        Ok(KeyEvent::new(Key::Char('+'), KeyModifiers::NONE))
+    );
+    assert!(parse_key("f13").is_err());
+    assert!(parse_key("hyper+q").is_err());
+    assert!(parse_key("shift+q").is_err());
+}
+
+#[test]
+fn test_keymap_rejects_duplicate_keys() {
+    assert_eq!(
+        Keymap::parse("help = \"q\""),
+        Err("q is bound to both quit and help".to_string())
+    );
+    assert!(Keymap::parse("quit = [\"x\", \"x\"]").is_err());
+}
+
+#[test]
+fn test_keymap_keeps_defaults_for_actions_left_out() {
+    let keymap = Keymap::parse("hints = \"t\"").unwrap();
+    assert_eq!(keymap.keys(Action::ToggleHints), ["t"]);
+    assert_eq!(keymap.keys(Action::Quit), ["q", "ctrl+c"]);
+    assert_eq!(keymap.keys(Action::Help), ["?"]);
+    assert_eq!(
+        keymap.action(&KeyEvent::new(Key::Char('?'), KeyModifiers::SHIFT)),
+        Some(Action::Help)
+    );
+    assert_eq!(
+        keymap.action(&KeyEvent::new(Key::Char('h'), KeyModifiers::NONE)),
+        None
+    );
+}
+
+/// A panel for three players with five dice each, after `bids`
+fn choice_panel(bids: &[(usize, u8)]) -> ChoicePanel {
+    let game = ScenarioBuilder::new()
+        .player(ME, &[1, 2, 3, 4, 5])
+        .player(LEFT, &[1, 2, 3, 4, 5])
+        .player(RIGHT, &[1, 2, 3, 4, 5])
+        .current_player(ME)
+        .bids(bids)
+        .build()
+        .unwrap();
+    ChoicePanel::new(game.legal_calls(), 15)
+}
+
+fn press(panel: &mut ChoicePanel, keys: &[Key]) -> Option<Msg> {
+    keys.iter()
+        .map(|key| panel.on(Event::<UserEvent>::Keyboard(KeyEvent::from(*key))))
+        .last()
+        .flatten()
+}
+
+fn type_keys(panel: &mut ChoicePanel, typed: &str) {
+    let keys: Vec<Key> = typed.chars().map(Key::Char).collect();
+    press(panel, &keys);
+}
+
+fn assert_selected(panel: &ChoicePanel, call: DiceCall) {
+    assert_eq!(
+        panel.state(),
+        State::One(StateValue::Usize(encode_call(&call)))
+    );
+}
+
+fn raise(count: usize, face: u8) -> DiceCall {
+    DiceCall::Increase {
+        count,
+        value: Dice::new(face).unwrap(),
+    }
+}
+
+#[test]
+fn test_choice_panel_starts_on_lowest_legal_raise() {
+    assert_selected(&choice_panel(&[]), raise(1, 1));
+    assert_selected(&choice_panel(&[(4, 3)]), raise(5, 1));
+}
+
+#[test]
+fn test_choice_panel_first_digit_replaces_count() {
+    let mut panel = choice_panel(&[]);
+    type_keys(&mut panel, "3");
+    assert_selected(&panel, raise(3, 1));
+    let mut panel = choice_panel(&[(4, 3)]);
+    type_keys(&mut panel, "3");
+    assert_selected(&panel, raise(3, 1));
+}
+
+#[test]
+fn test_choice_panel_later_digits_append_within_max() {
+    let mut panel = choice_panel(&[]);
+    type_keys(&mut panel, "12");
+    assert_selected(&panel, raise(12, 1));
+    // 127 is more dice than are in play, so 7 starts again
+    type_keys(&mut panel, "7");
+    assert_selected(&panel, raise(7, 1));
+}
+
+#[test]
+fn test_choice_panel_digit_after_other_key_replaces_count() {
+    let mut panel = choice_panel(&[]);
+    type_keys(&mut panel, "1");
+    press(&mut panel, &[Key::Right, Key::Left]);
+    type_keys(&mut panel, "4");
+    assert_selected(&panel, raise(4, 1));
+    type_keys(&mut panel, "1x2");
+    assert_selected(&panel, raise(2, 1));
+    type_keys(&mut panel, "1");
+    press(&mut panel, &[Key::Up]);
+    type_keys(&mut panel, "3");
+    assert_selected(&panel, raise(3, 1));
+}
+
+#[test]
+fn test_choice_panel_selects_face_and_challenges() {
+    let mut panel = choice_panel(&[(2, 3)]);
+    press(&mut panel, &[Key::Right]);
+    type_keys(&mut panel, "5");
+    assert_selected(&panel, raise(3, 5));
+    press(&mut panel, &[Key::Right]);
+    assert_selected(&panel, DiceCall::Bullshit);
+    press(&mut panel, &[Key::Right]);
+    assert_selected(&panel, DiceCall::SpotOn);
+}
+
+#[test]
+fn test_choice_panel_greys_out_illegal_calls() {
+    let panel = choice_panel(&[]);
+    assert!(panel.legal(Field::Count));
+    assert!(panel.legal(Field::Face));
+    assert!(!panel.legal(Field::Bullshit));
+    assert!(!panel.legal(Field::SpotOn));
+
+    let mut panel = choice_panel(&[(4, 3)]);
+    assert!(panel.legal(Field::Bullshit));
+    assert!(panel.legal(Field::SpotOn));
+    type_keys(&mut panel, "4");
+    assert!(!panel.legal(Field::Count));
+    assert!(!panel.legal(Field::Face));
+    press(&mut panel, &[Key::Up]);
+    assert!(panel.legal(Field::Count));
+}
+
+#[test]
+fn test_choice_panel_proposes_then_confirms() {
+    let mut panel = choice_panel(&[]);
+    type_keys(&mut panel, "3");
+    assert_eq!(
+        press(&mut panel, &[Key::Enter]),
+        Some(Msg::ProposeCall(raise(3, 1)))
+    );
+    panel.attr(CONFIRMING, AttrValue::Flag(true));
+    assert_eq!(press(&mut panel, &[Key::Enter]), Some(Msg::ConfirmCall));
+}
+
+#[test]
+fn test_choice_panel_cancels_and_changes_clear_prompt() {
+    let mut panel = choice_panel(&[]);
+    assert_eq!(press(&mut panel, &[Key::Esc]), None);
+    panel.attr(CONFIRMING, AttrValue::Flag(true));
+    assert_eq!(press(&mut panel, &[Key::Esc]), Some(Msg::CancelCall));
+    assert_eq!(panel.query(CONFIRMING), Some(AttrValue::Flag(false)));
+
+    panel.attr(CONFIRMING, AttrValue::Flag(true));
+    panel.attr(
+        ERROR,
+        AttrValue::String("New bid must be higher than previous".to_string()),
+    );
+    assert_eq!(press(&mut panel, &[Key::Up]), Some(Msg::Redraw));
+    assert_eq!(panel.query(CONFIRMING), Some(AttrValue::Flag(false)));
+    assert_eq!(panel.query(ERROR), Some(AttrValue::String(String::new())));
+    assert_eq!(
+        press(&mut panel, &[Key::Enter]),
+        Some(Msg::ProposeCall(raise(2, 1)))
+    );
+}
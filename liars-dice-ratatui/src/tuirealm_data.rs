@@ -1,12 +1,73 @@
+use crate::components::bid_history::HistoryEntry;
+use crate::components::player_table::SeatRow;
+use crate::setup::GameSetup;
+use liars_dice_lib::DiceCall;
+use std::mem::discriminant;
+use uuid::Uuid;
+
 #[derive(Debug, PartialEq)]
 pub enum Msg {
     AppClose,
+    /// A component changed how it looks, without anything else happening
+    Redraw,
+    /// The setup form was submitted
+    StartGame(GameSetup),
+    /// The current player picked a call, which still needs checking and confirming
+    ProposeCall(DiceCall),
+    ConfirmCall,
+    CancelCall,
+    /// The next player has the keyboard and can see their dice
+    HandedOver,
+    /// Shows or hides the odds for the current player
+    ToggleHints,
+    /// Shows or hides the list of keys
+    ToggleHelp,
+    /// Leaves the game for the setup form
+    NewGame,
+    /// Everyone has seen the dice from the last challenge
+    RevealDone,
+    /// A bot picked its call for the given turn
+    BotCalled {
+        turn: usize,
+        player_id: Uuid,
+        call: DiceCall,
+    },
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum Id {
     PlayerTable,
+    BidHistory,
+    ChoicePanel,
+    Hand,
+    PassScreen,
+    BotStatus,
+    SetupForm,
+    Reveal,
+    HintOverlay,
+    HelpOverlay,
+    ReplayViewer,
 }
 
-#[derive(Debug, PartialEq)]
-pub enum UserEvent {}
+#[derive(Debug, Clone)]
+pub enum UserEvent {
+    /// The game moved on, so every seat is shown afresh
+    SeatsChanged(Vec<SeatRow>),
+    /// Every call so far, whenever another is made
+    HistoryChanged(Vec<HistoryEntry>),
+    /// A bot's call, sent from the bot thread
+    BotCalled {
+        turn: usize,
+        player_id: Uuid,
+        call: DiceCall,
+    },
+}
+
+/// Only the kind of event matters, so subscriptions match whatever it carries
+impl PartialEq for UserEvent {
+    fn eq(&self, other: &Self) -> bool {
+        discriminant(self) == discriminant(other)
+    }
+}
+
+impl Eq for UserEvent {}
@@ -0,0 +1,59 @@
+//! Trains a heads up policy through self-play, saving checkpoints that the lib's neural agent can load.
+mod policy;
+#[cfg(test)]
+mod test;
+mod train;
+
+use std::path::PathBuf;
+use std::process::exit;
+use train::TrainConfig;
+
+const USAGE: &str = "usage: liars-dice-train [--iterations <n>] [--games <n>] [--hidden <n>,<n>...] [--lr <x>] [--entropy <x>] [--dice <n>] [--seed <n>] [--checkpoint-every <n>] [--eval-games <n>] [--out <dir>]";
+
+fn main() {
+    let mut config = TrainConfig::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| fail(&format!("{arg} needs a value")))
+        };
+        match arg.as_str() {
+            "--iterations" => config.iterations = parse_number(&value()),
+            "--games" => config.games_per_iteration = parse_number(&value()),
+            "--hidden" => {
+                config.hidden = value().split(',').map(parse_number).collect();
+            }
+            "--lr" => config.learning_rate = parse_number(&value()),
+            "--entropy" => config.entropy = parse_number(&value()),
+            "--dice" => config.starting_dice = parse_number(&value()),
+            "--seed" => config.seed = parse_number(&value()),
+            "--checkpoint-every" => config.checkpoint_every = parse_number(&value()),
+            "--eval-games" => config.eval_games = parse_number(&value()),
+            "--out" => config.out = PathBuf::from(value()),
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => fail(&format!("Unknown argument {arg}")),
+        }
+    }
+    if config.iterations == 0 || config.games_per_iteration == 0 || config.checkpoint_every == 0 {
+        fail("Iterations, games and checkpoint interval must be at least one");
+    }
+    if let Err(e) = train::train(&config) {
+        eprintln!("Training failed: {e}");
+        exit(1);
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("{value} is not a valid number")))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    exit(1)
+}
@@ -0,0 +1,124 @@
+//! A small MLP over the observation encoding, with a logit per action.
+use candle_core::{D, DType, Device, Result, Tensor};
+use candle_nn::ops::log_softmax;
+use candle_nn::{Linear, Module, VarBuilder, linear};
+use liars_dice_lib::agent::Agent;
+use liars_dice_lib::encoding::{ObservationEncoder, decode_call};
+use liars_dice_lib::{DiceCall, LiarsDiceGame};
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand_chacha::ChaCha12Rng;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Added to the logits of illegal actions, so they get no probability after the softmax
+const ILLEGAL_LOGIT: f64 = -1e9;
+
+/// Describes the network next to its weights, so it can be loaded without this crate.
+/// Every layer is a `<name>.weight` and `<name>.bias` in the safetensors file, with the activation between them.
+#[derive(Debug, Clone, Serialize)]
+pub struct Sidecar {
+    pub players: usize,
+    pub starting_dice: u8,
+    pub activation: String,
+    pub layers: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct Policy {
+    layers: Vec<Linear>,
+    pub encoder: ObservationEncoder,
+}
+
+impl Policy {
+    pub fn new(vb: VarBuilder, encoder: ObservationEncoder, hidden: &[usize]) -> Result<Self> {
+        let sizes: Vec<usize> = std::iter::once(encoder.size())
+            .chain(hidden.iter().copied())
+            .chain([encoder.action_count()])
+            .collect();
+        let layers = sizes
+            .windows(2)
+            .enumerate()
+            .map(|(i, size)| linear(size[0], size[1], vb.pp(format!("fc{i}"))))
+            .collect::<Result<_>>()?;
+        Ok(Self { layers, encoder })
+    }
+
+    pub fn sidecar(&self) -> Sidecar {
+        Sidecar {
+            players: self.encoder.players,
+            starting_dice: self.encoder.starting_dice,
+            activation: "relu".to_string(),
+            layers: (0..self.layers.len()).map(|i| format!("fc{i}")).collect(),
+        }
+    }
+
+    /// Raw logits for a batch of observations
+    pub fn forward(&self, observations: &Tensor) -> Result<Tensor> {
+        let mut xs = observations.clone();
+        for (i, layer) in self.layers.iter().enumerate() {
+            xs = layer.forward(&xs)?;
+            if i + 1 < self.layers.len() {
+                xs = xs.relu()?;
+            }
+        }
+        Ok(xs)
+    }
+
+    /// Log probabilities for a batch, with the illegal actions of each row masked out
+    pub fn log_probs(&self, observations: &Tensor, masks: &Tensor) -> Result<Tensor> {
+        let logits = self.forward(observations)?;
+        let penalty = masks.affine(-ILLEGAL_LOGIT, ILLEGAL_LOGIT)?;
+        log_softmax(&(logits + penalty)?, D::Minus1)
+    }
+
+    /// Probability of every action for the current player
+    pub fn action_probs(&self, game: &LiarsDiceGame, player_id: &Uuid) -> Result<Vec<f32>> {
+        let row = |values: Vec<f32>| {
+            let len = values.len();
+            Tensor::from_vec(values, (1, len), &Device::Cpu)
+        };
+        let observation = row(self.encoder.encode(game, player_id))?;
+        let mask = row(self.encoder.legal_action_mask(game))?;
+        self.log_probs(&observation, &mask)?
+            .exp()?
+            .squeeze(0)?
+            .to_dtype(DType::F32)?
+            .to_vec1()
+    }
+}
+
+/// Plays a policy, sampling from it while training or taking its favourite action when evaluating
+pub struct PolicyAgent {
+    policy: Policy,
+    rng: ChaCha12Rng,
+    greedy: bool,
+}
+
+impl PolicyAgent {
+    pub fn new(policy: Policy, rng: ChaCha12Rng, greedy: bool) -> Self {
+        Self {
+            policy,
+            rng,
+            greedy,
+        }
+    }
+}
+
+impl Agent for PolicyAgent {
+    fn choose_call(&mut self, game: &LiarsDiceGame, player_id: Uuid) -> DiceCall {
+        let probs = self
+            .policy
+            .action_probs(game, &player_id)
+            .expect("Policy should run on the CPU");
+        let action = match self.greedy {
+            true => (0..probs.len())
+                .max_by(|a, b| probs[*a].total_cmp(&probs[*b]))
+                .unwrap(),
+            false => WeightedIndex::new(&probs)
+                .expect("At least one action should be legal")
+                .sample(&mut self.rng),
+        };
+        decode_call(action)
+    }
+}
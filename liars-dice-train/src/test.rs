@@ -0,0 +1,86 @@
+use crate::policy::{Policy, PolicyAgent};
+use crate::train::{TrainConfig, save, train};
+use candle_core::{DType, Device};
+use candle_nn::{VarBuilder, VarMap};
+use liars_dice_lib::agent::{Agent, NeuralAgent, NeuralNetwork, Selection};
+use liars_dice_lib::encoding::ObservationEncoder;
+use liars_dice_lib::{LiarsDiceGame, LiarsDiceState};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// A directory under the system temp dir, removed again when dropped
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn started_game() -> (LiarsDiceGame, Uuid) {
+    let players = [Uuid::from_u128(1), Uuid::from_u128(2)];
+    let mut game = LiarsDiceGame::new(ChaCha12Rng::seed_from_u64(3), 2, &players);
+    if let LiarsDiceState::Start(start) = game.get_state() {
+        start.initialise_game();
+    }
+    let player_id = game.current_player().unwrap();
+    (game, player_id)
+}
+
+fn assert_same_probs(policy: &Policy, network: &NeuralNetwork) {
+    let (game, player_id) = started_game();
+    let expected = policy.action_probs(&game, &player_id).unwrap();
+    let loaded = network.action_probs(&game, &player_id);
+    assert_eq!(expected.len(), loaded.len());
+    for (a, b) in expected.iter().zip(&loaded) {
+        assert!((a - b).abs() < 1e-5, "{a} != {b}");
+    }
+}
+
+#[test]
+fn test_checkpoint_reloads_as_neural_agent() {
+    let varmap = VarMap::new();
+    let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+    let policy = Policy::new(vb, ObservationEncoder::new(2, 2), &[16, 8]).unwrap();
+    let dir = ScratchDir::new("checkpoint");
+    let path = dir.0.join("policy");
+    save(&policy, &varmap, &path).unwrap();
+    let network = NeuralNetwork::load(path.with_extension("safetensors")).unwrap();
+    assert_same_probs(&policy, &network);
+
+    let (game, player_id) = started_game();
+    let rng = ChaCha12Rng::seed_from_u64(4);
+    let mut trained = PolicyAgent::new(policy, rng.clone(), true);
+    let mut reloaded = NeuralAgent::new(network, Selection::Argmax, rng);
+    assert_eq!(
+        trained.choose_call(&game, player_id),
+        reloaded.choose_call(&game, player_id)
+    );
+}
+
+#[test]
+fn test_single_iteration_trains_and_saves() {
+    let dir = ScratchDir::new("train");
+    let config = TrainConfig {
+        iterations: 1,
+        games_per_iteration: 2,
+        hidden: vec![8],
+        eval_games: 2,
+        out: dir.0.clone(),
+        ..TrainConfig::default()
+    };
+    let policy = train(&config).unwrap();
+    assert!(dir.0.join("policy-00001.json").exists());
+    let network = NeuralNetwork::load(dir.0.join("policy-00001.safetensors")).unwrap();
+    assert_same_probs(&policy, &network);
+}
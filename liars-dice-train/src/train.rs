@@ -0,0 +1,199 @@
+//! REINFORCE through self-play, with the batch mean return as the baseline.
+use crate::policy::{Policy, PolicyAgent};
+use candle_core::{D, DType, Device, Result, Tensor};
+use candle_nn::{AdamW, Optimizer, ParamsAdamW, VarBuilder, VarMap};
+use liars_dice_lib::LiarsDiceGame;
+use liars_dice_lib::agent::{Agent, IsmctsAgent, IsmctsConfig, RandomAgent};
+use liars_dice_lib::dataset::{DecisionRow, record_game};
+use liars_dice_lib::encoding::ObservationEncoder;
+use liars_dice_lib::tournament::{Tournament, TournamentConfig, TournamentFormat};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Games are always heads up, so checkpoints can be evaluated in the tournament runner
+const PLAYERS: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct TrainConfig {
+    pub iterations: usize,
+    /// Self-play games behind every gradient step
+    pub games_per_iteration: usize,
+    pub hidden: Vec<usize>,
+    pub learning_rate: f64,
+    /// Weight of the entropy bonus, which keeps the policy from collapsing too early
+    pub entropy: f64,
+    pub starting_dice: u8,
+    pub seed: u64,
+    pub checkpoint_every: usize,
+    /// Games against each baseline at every checkpoint
+    pub eval_games: usize,
+    pub out: PathBuf,
+}
+
+impl Default for TrainConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            games_per_iteration: 64,
+            hidden: vec![128, 128],
+            learning_rate: 1e-3,
+            entropy: 0.01,
+            starting_dice: 2,
+            seed: 0,
+            checkpoint_every: 50,
+            eval_games: 100,
+            out: PathBuf::from("checkpoints"),
+        }
+    }
+}
+
+/// Trains a fresh policy, saving and evaluating checkpoints along the way, and returns it as it ends up
+pub fn train(config: &TrainConfig) -> Result<Policy> {
+    let encoder = ObservationEncoder::new(PLAYERS, config.starting_dice);
+    let varmap = VarMap::new();
+    let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+    let policy = Policy::new(vb, encoder, &config.hidden)?;
+    let params = ParamsAdamW {
+        lr: config.learning_rate,
+        weight_decay: 0.0,
+        ..ParamsAdamW::default()
+    };
+    let mut optimizer = AdamW::new(varmap.all_vars(), params)?;
+    let mut rng = ChaCha12Rng::seed_from_u64(config.seed);
+    std::fs::create_dir_all(&config.out)?;
+    for iteration in 1..=config.iterations {
+        let rows = self_play(&policy, config, &mut rng)?;
+        let loss = reinforce_loss(&policy, &rows, config.entropy)?;
+        optimizer.backward_step(&loss)?;
+        println!(
+            "iteration {iteration:>5}  loss {:>8.4}  decisions per game {:>5.1}",
+            loss.to_scalar::<f32>()?,
+            rows.len() as f64 / config.games_per_iteration as f64
+        );
+        if iteration % config.checkpoint_every == 0 || iteration == config.iterations {
+            let path = config.out.join(format!("policy-{iteration:05}"));
+            save(&policy, &varmap, &path)?;
+            println!("saved {}", path.display());
+            evaluate(&policy, config, iteration as u64);
+        }
+    }
+    Ok(policy)
+}
+
+/// Plays a batch of games with the policy in every seat
+fn self_play(
+    policy: &Policy,
+    config: &TrainConfig,
+    rng: &mut ChaCha12Rng,
+) -> Result<Vec<DecisionRow>> {
+    let seats: Vec<Uuid> = (0..PLAYERS)
+        .map(|i| Uuid::from_u128(i as u128 + 1))
+        .collect();
+    let mut rows = vec![];
+    for game_index in 0..config.games_per_iteration {
+        let mut agents: BTreeMap<Uuid, Box<dyn Agent>> = seats
+            .iter()
+            .map(|seat| {
+                let agent =
+                    PolicyAgent::new(policy.clone(), ChaCha12Rng::from_rng(&mut *rng), false);
+                (*seat, Box::new(agent) as Box<dyn Agent>)
+            })
+            .collect();
+        let mut game = LiarsDiceGame::new(
+            ChaCha12Rng::from_rng(&mut *rng),
+            config.starting_dice,
+            &seats,
+        );
+        let game_rows = record_game(game_index as u64, &mut game, &mut agents)
+            .map_err(|(_, e)| candle_core::Error::Msg(e.to_string()))?;
+        rows.extend(game_rows);
+    }
+    Ok(rows)
+}
+
+fn reinforce_loss(policy: &Policy, rows: &[DecisionRow], entropy: f64) -> Result<Tensor> {
+    let device = Device::Cpu;
+    let n = rows.len();
+    let stack = |values: Vec<f32>, width: usize| Tensor::from_vec(values, (n, width), &device);
+    let observations = stack(
+        rows.iter()
+            .flat_map(|r| r.observation.iter().copied())
+            .collect(),
+        policy.encoder.size(),
+    )?;
+    let masks = stack(
+        rows.iter()
+            .flat_map(|r| r.legal_mask.iter().copied())
+            .collect(),
+        policy.encoder.action_count(),
+    )?;
+    let actions = Tensor::from_vec(
+        rows.iter().map(|r| r.action).collect::<Vec<u32>>(),
+        (n, 1),
+        &device,
+    )?;
+    let returns: Vec<f32> = rows.iter().map(|r| r.final_return).collect();
+    let baseline = returns.iter().sum::<f32>() / n as f32;
+    let advantages = Tensor::from_vec(
+        returns.iter().map(|r| r - baseline).collect::<Vec<f32>>(),
+        n,
+        &device,
+    )?;
+    let log_probs = policy.log_probs(&observations, &masks)?;
+    let chosen = log_probs.gather(&actions, D::Minus1)?.squeeze(D::Minus1)?;
+    let policy_loss = (chosen * advantages)?.mean_all()?.neg()?;
+    let entropies = (log_probs.exp()? * &log_probs)?.sum(D::Minus1)?.neg()?;
+    policy_loss - (entropies.mean_all()? * entropy)?
+}
+
+/// Writes `<path>.safetensors` with the weights and `<path>.json` describing the network
+pub fn save(policy: &Policy, varmap: &VarMap, path: &Path) -> Result<()> {
+    varmap.save(path.with_extension("safetensors"))?;
+    let sidecar = serde_json::to_string_pretty(&policy.sidecar())
+        .map_err(|e| candle_core::Error::Msg(e.to_string()))?;
+    std::fs::write(path.with_extension("json"), sidecar)?;
+    Ok(())
+}
+
+/// Plays the greedy policy against each baseline bot in its own match, so the bots never play each other
+fn evaluate(policy: &Policy, config: &TrainConfig, seed: u64) {
+    evaluate_against(policy, config, seed, "random", random_baseline);
+    evaluate_against(policy, config, seed, "ismcts:100", ismcts_baseline);
+}
+
+fn evaluate_against(
+    policy: &Policy,
+    config: &TrainConfig,
+    seed: u64,
+    name: &str,
+    baseline: fn(ChaCha12Rng) -> Box<dyn Agent>,
+) {
+    let mut tournament = Tournament::new(TournamentConfig {
+        format: TournamentFormat::RoundRobin,
+        games_per_match: config.eval_games,
+        starting_dice: config.starting_dice,
+        seed,
+    });
+    let policy = policy.clone();
+    tournament.register("policy", move |rng| {
+        Box::new(PolicyAgent::new(policy.clone(), rng, true)) as Box<dyn Agent>
+    });
+    tournament.register(name, baseline);
+    print!("{}", tournament.run());
+}
+
+fn random_baseline(rng: ChaCha12Rng) -> Box<dyn Agent> {
+    Box::new(RandomAgent::new(rng))
+}
+
+fn ismcts_baseline(mut rng: ChaCha12Rng) -> Box<dyn Agent> {
+    let config = IsmctsConfig {
+        iterations: 100,
+        ..IsmctsConfig::default()
+    };
+    let rollout = RandomAgent::new(ChaCha12Rng::from_rng(&mut rng));
+    Box::new(IsmctsAgent::new(config, rng, rollout))
+}